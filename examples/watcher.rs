@@ -1,12 +1,12 @@
 use aws_sdk_s3::{Client, Config};
-use conditional_s3_fetch::{Cbor, File, Json, Parse};
+use conditional_s3_fetch::{AsyncParse, Cbor, File, Json, Refresh};
 use futures::future::FutureExt;
 use std::time::Duration;
 use tokio::time::sleep;
 
 async fn fetch_file<T>(path: &str) -> File<T>
 where
-    T: Parse,
+    T: AsyncParse + Default,
 {
     let conf = Config::builder()
         .credentials_provider(aws_sdk_s3::config::Credentials::new(
@@ -26,9 +26,13 @@ where
 
     for x in 1..5 {
         println!("{}x - Fetching data for {:?}", x, &file);
-        match file.fetch(&s3_client).await {
-            Ok(Some(new)) => file = new,
-            Ok(None) => println!("No modification for {:?}", &file),
+        match file.refresh(&s3_client).await {
+            Ok(Refresh::Unchanged) => println!("No modification for {:?}", &file),
+            Ok(Refresh::FirstLoad) => println!("Loaded {:?}", &file),
+            Ok(Refresh::Updated { previous_etag, .. }) => {
+                println!("Replaced {} with {:?}", previous_etag, &file)
+            }
+            Ok(Refresh::Deleted { .. }) => println!("Deleted {:?}", &file),
             Err(e) => eprintln!("Error: {:?} on {:?}", e, &file),
         }
         println!("Scheduling another update soon");
@@ -0,0 +1,63 @@
+//! Bincode parser implementation (feature: `bincode`)
+//!
+//! Parser implementation to read [bincode](https://docs.rs/bincode) data into a deserialized object.
+//!
+//! The encoding options are provided at the type level, using the [`config`] types from bincode.
+//! When not provided, the [`config::standard`] configuration is used: little endian, variable int encoding and no size limit.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize)]
+//! # struct MyStruct;
+//! use conditional_s3_fetch::{File, Bincode};
+//! use conditional_s3_fetch::bincode::config::{BigEndian, Configuration, Fixint, Limit};
+//!
+//! let file = File::<Bincode<MyStruct>>::unloaded("bucket", "/data/index.bin");
+//!
+//! type Legacy = Configuration<BigEndian, Fixint, Limit<{ 16 * 1024 * 1024 }>>;
+//! let file = File::<Bincode<MyStruct, Legacy>>::unloaded("bucket", "/data/index.bin");
+//! ```
+use bytes::Bytes;
+
+pub use ::bincode::config;
+
+/// Parser implementation to read bincode data into a deserialized object.
+///
+/// The second type parameter selects the encoding options, such as endianness, int encoding and size limit.
+/// The whole content must be consumed by the decoding process, otherwise it is considered an error.
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize)]
+/// # struct MyStruct;
+/// use conditional_s3_fetch::{File, Bincode};
+/// use conditional_s3_fetch::bincode::config::{Configuration, Fixint, LittleEndian};
+///
+/// let file = File::<Bincode<MyStruct, Configuration<LittleEndian, Fixint>>>::unloaded("bucket", "/data/index.bin");
+/// ```
+#[derive(Debug)]
+pub struct Bincode<T, C = config::Configuration>(std::marker::PhantomData<fn() -> (T, C)>);
+
+impl<T, C> Default for Bincode<T, C> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+impl<T, C> crate::Parse for Bincode<T, C>
+where
+    T: serde::de::DeserializeOwned,
+    C: config::Config + Default,
+{
+    type Output = T;
+
+    fn parse(bytes: Bytes) -> crate::BoxedResult<Self::Output> {
+        let (value, read) = ::bincode::serde::decode_from_slice(&bytes, C::default())?;
+        if read != bytes.len() {
+            return Err(format!("{} trailing bytes after bincode data", bytes.len() - read).into());
+        }
+        Ok(value)
+    }
+}
@@ -0,0 +1,49 @@
+//! Response body provided to asynchronous parsers
+use aws_sdk_s3::primitives::{ByteStream, ByteStreamError};
+use bytes::Bytes;
+
+/// Content of the S3 object, as it is downloaded
+///
+/// Provided to [`AsyncParse`](crate::AsyncParse) implementations, which can read it chunk by chunk with [`Body::try_next`],
+/// or wait for the whole content with [`Body::bytes`].
+///
+/// Read errors returned by these methods are reported as [`Error::ReadError`](crate::Error::ReadError) by the fetch,
+/// when propagated as-is by the parser.
+#[derive(Debug)]
+pub struct Body(ByteStream);
+
+impl Body {
+    /// Returns the next chunk of content, or `None` once the download is complete
+    ///
+    /// # Errors
+    /// Returns an error if the content could not be read.
+    pub async fn try_next(&mut self) -> Result<Option<Bytes>, ByteStreamError> {
+        self.0.try_next().await
+    }
+
+    /// Waits for the download to complete, returning the whole content
+    ///
+    /// # Errors
+    /// Returns an error if the content could not be read.
+    pub async fn bytes(self) -> Result<Bytes, ByteStreamError> {
+        Ok(self.0.collect().await?.into_bytes())
+    }
+
+    /// Converts the body into the underlying [`ByteStream`]
+    pub fn into_inner(self) -> ByteStream {
+        self.0
+    }
+}
+
+impl From<ByteStream> for Body {
+    fn from(stream: ByteStream) -> Self {
+        Self(stream)
+    }
+}
+
+/// Creates a body with the given content, such as to call parsers directly on tests
+impl From<Bytes> for Body {
+    fn from(bytes: Bytes) -> Self {
+        Self(ByteStream::from(bytes))
+    }
+}
@@ -0,0 +1,123 @@
+//! Borrowing parsers (feature: `borrowed`)
+//!
+//! [`Parse`](crate::Parse) produces owned outputs, so every string has to be copied out of the fetched buffer.
+//! The [`ParseBorrowed`] trait allows outputs which keep references into the fetched [`Bytes`] instead,
+//! such as structs with `&'a str` fields.
+//!
+//! The parsed output is kept next to the buffer it borrows from, using a [`Yoke`].
+//! Use the [`Borrowed`] parser to fetch files with a [`ParseBorrowed`] implementation.
+//!
+//! Outputs must implement [`Yokeable`], which can be derived with the `derive` feature of the [`yoke`] crate.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize, yoke::Yokeable)]
+//! # struct MyStruct<'a> { name: &'a str }
+//! use conditional_s3_fetch::{Borrowed, Cbor, File};
+//!
+//! let file = File::<Borrowed<Cbor<MyStruct<'static>>>>::unloaded("bucket", "/data/key.cbor");
+//!
+//! if let Some(content) = file.as_content() {
+//!     println!("{}", content.get().name);
+//! }
+//! ```
+use std::{marker::PhantomData, ops::Deref};
+
+use bytes::Bytes;
+use yoke::{CloneableCart, Yoke, Yokeable};
+
+/// Parsed output which borrows from the fetched content
+///
+/// Use [`Yoke::get`] to access the output.
+pub type Yoked<T> = Yoke<T, Cart>;
+
+/// Trait to parse the [`File`](crate::File) content into an output borrowing from the fetched [`Bytes`]
+///
+/// `Output` is the `'static` form of the borrowing type, such as `MyStruct<'static>`.
+///
+/// # Example
+///
+/// ```rust
+/// use conditional_s3_fetch::{BoxedResult, Borrowed, File, ParseBorrowed};
+///
+/// struct FirstLine;
+///
+/// impl ParseBorrowed for FirstLine {
+///     type Output = &'static str;
+///
+///     fn parse_borrowed(bytes: &[u8]) -> BoxedResult<&str> {
+///         let text = std::str::from_utf8(bytes)?;
+///         Ok(text.lines().next().unwrap_or_default())
+///     }
+/// }
+///
+/// let file = File::<Borrowed<FirstLine>>::unloaded("my-bucket", "/my/path.txt");
+/// ```
+pub trait ParseBorrowed {
+    type Output: for<'a> Yokeable<'a>;
+
+    /// Parse the file content from S3, borrowing from it
+    ///
+    /// # Errors
+    /// Returns an error if the content could not be parsed.
+    fn parse_borrowed(bytes: &[u8]) -> crate::BoxedResult<<Self::Output as Yokeable<'_>>::Output>;
+}
+
+/// Parser implementation for [`ParseBorrowed`] parsers
+///
+/// The output is a [`Yoked`] value, which keeps the fetched content alive while it is borrowed.
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize, yoke::Yokeable)]
+/// # struct MyStruct<'a> { name: &'a str }
+/// use conditional_s3_fetch::{Borrowed, Cbor, File};
+///
+/// let file = File::<Borrowed<Cbor<MyStruct<'static>>>>::unloaded("bucket", "/data/key.cbor");
+/// ```
+#[derive(Debug)]
+pub struct Borrowed<P>(PhantomData<fn() -> P>);
+
+impl<P> Default for Borrowed<P> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P> crate::Parse for Borrowed<P>
+where
+    P: ParseBorrowed,
+{
+    type Output = Yoked<P::Output>;
+
+    fn parse(bytes: Bytes) -> crate::BoxedResult<Self::Output> {
+        Yoke::try_attach_to_cart(Cart(bytes), |bytes| P::parse_borrowed(bytes))
+    }
+}
+
+/// Fetched content kept alive by a [`Yoked`] output
+#[derive(Debug, Clone)]
+pub struct Cart(Bytes);
+
+impl Cart {
+    /// Returns the fetched content
+    pub fn bytes(&self) -> &Bytes {
+        &self.0
+    }
+}
+
+impl Deref for Cart {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+// SAFETY: `Bytes` points to a shared, immutable buffer which does not move when the handle itself is moved.
+unsafe impl stable_deref_trait::StableDeref for Cart {}
+
+// SAFETY: cloning `Bytes` returns a new handle to the same buffer, without copying it.
+unsafe impl CloneableCart for Cart {}
@@ -25,7 +25,13 @@ use bytes::Bytes;
 /// let file = File::<Cbor<MyStruct>>::unloaded("bucket", "/data/key.cbor");
 /// ```
 #[derive(Debug)]
-pub struct Cbor<T>(std::marker::PhantomData<T>);
+pub struct Cbor<T>(std::marker::PhantomData<fn() -> T>);
+
+impl<T> Default for Cbor<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
 
 impl<T> crate::Parse for Cbor<T>
 where
@@ -34,6 +40,52 @@ where
     type Output = T;
 
     fn parse(bytes: Bytes) -> crate::BoxedResult<Self::Output> {
-        Ok(cbor4ii::serde::from_slice(&bytes)?)
+        Ok(from_slice(&bytes)?)
+    }
+}
+
+impl<T> crate::SerdeFormat for Cbor<T> {
+    fn from_slice<U>(bytes: &[u8]) -> crate::BoxedResult<U>
+    where
+        U: serde::de::DeserializeOwned,
+    {
+        Ok(from_slice(bytes)?)
+    }
+}
+
+impl<T> crate::TypedFormat for Cbor<T> {}
+
+/// Deserialize a Cbor document, reporting the path to the failing field
+fn from_slice<'a, T>(bytes: &'a [u8]) -> Result<T, crate::DeserializeError>
+where
+    T: serde::Deserialize<'a>,
+{
+    let reader = cbor4ii::core::utils::SliceReader::new(bytes);
+    let mut deserializer = cbor4ii::serde::Deserializer::new(reader);
+    serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(|e| crate::DeserializeError::new(&e))
+}
+
+/// Borrowing parser implementation, keeping string and byte slices into the fetched content (feature: `borrowed`)
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize, yoke::Yokeable)]
+/// # struct MyStruct<'a> { name: &'a str }
+/// use conditional_s3_fetch::{Borrowed, Cbor, File};
+///
+/// let file = File::<Borrowed<Cbor<MyStruct<'static>>>>::unloaded("bucket", "/data/key.cbor");
+/// ```
+#[cfg(feature = "borrowed")]
+impl<T> crate::ParseBorrowed for Cbor<T>
+where
+    T: for<'a> yoke::Yokeable<'a>,
+    for<'a> <T as yoke::Yokeable<'a>>::Output: serde::Deserialize<'a>,
+{
+    type Output = T;
+
+    fn parse_borrowed(bytes: &[u8]) -> crate::BoxedResult<<T as yoke::Yokeable<'_>>::Output> {
+        Ok(from_slice(bytes)?)
     }
 }
@@ -0,0 +1,377 @@
+//! Generic parsers wrapping other parsers
+//!
+//! Combinators post-process, validate or replace the output of another parser,
+//! and compose at the type level, such as `File<Validated<Json<Config>, MyRules>>`.
+//!
+//! They are implemented as [`StreamParse`], so any [`Parse`](crate::Parse), [`ParseWithContext`](crate::ParseWithContext)
+//! or [`StreamParse`] implementation can be wrapped, keeping the streaming behavior of the inner parser.
+//!
+//! Combinators implement [`Default`] when their parts do, so they can be used with [`File::unloaded`](crate::File::unloaded).
+//! Otherwise, such as when using closures, provide the instance with [`File::with_parser`](crate::File::with_parser).
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize)]
+//! # struct Config { port: u16 }
+//! use conditional_s3_fetch::{BoxedResult, File, Json, ParseContext, Validated, Validator};
+//!
+//! #[derive(Default)]
+//! struct MyRules;
+//!
+//! impl Validator<Config> for MyRules {
+//!     fn validate(&self, config: &Config, _context: &ParseContext) -> BoxedResult<()> {
+//!         if config.port == 0 {
+//!             return Err("Port must not be zero".into());
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let file = File::<Validated<Json<Config>, MyRules>>::unloaded("my-bucket", "/my/config.json");
+//! ```
+use std::{error::Error, fmt};
+
+use bytes::Bytes;
+
+use crate::{BoxedResult, ParseContext, StreamParse};
+
+/// Trait to transform the output of a parser, used by [`Map`]
+///
+/// Implemented by any `Fn(T) -> O` closure.
+pub trait Mapper<T> {
+    type Output;
+
+    /// Transform the parsed output
+    fn map(&self, output: T) -> Self::Output;
+}
+
+impl<F, T, O> Mapper<T> for F
+where
+    F: Fn(T) -> O,
+{
+    type Output = O;
+
+    fn map(&self, output: T) -> O {
+        self(output)
+    }
+}
+
+/// Parser transforming the output of another parser with a [`Mapper`]
+///
+/// # Example
+///
+/// ```rust
+/// use conditional_s3_fetch::{File, Lines, Map};
+///
+/// let parser = Map::new(Lines, |lines: Vec<String>| lines.len());
+/// let file = File::with_parser("bucket", "/data/feed.txt", parser);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Map<P, F> {
+    parser: P,
+    mapper: F,
+}
+
+impl<P, F> Map<P, F> {
+    /// Transform the output of `parser` with `mapper`
+    pub fn new(parser: P, mapper: F) -> Self {
+        Self { parser, mapper }
+    }
+}
+
+impl<P, F> StreamParse for Map<P, F>
+where
+    P: StreamParse,
+    F: Mapper<P::Output>,
+{
+    type Output = F::Output;
+    type State = P::State;
+
+    fn start(&self, context: &ParseContext) -> Self::State {
+        self.parser.start(context)
+    }
+
+    fn feed(&self, state: &mut Self::State, chunk: Bytes) -> BoxedResult<()> {
+        self.parser.feed(state, chunk)
+    }
+
+    fn finish(&self, state: Self::State, context: &ParseContext) -> BoxedResult<Self::Output> {
+        let output = self.parser.finish(state, context)?;
+        Ok(self.mapper.map(output))
+    }
+}
+
+/// Trait to check the output of a parser, used by [`Validated`]
+///
+/// Implemented by any `Fn(&T) -> BoxedResult<()>` closure.
+pub trait Validator<T> {
+    /// Check the parsed output, returning an error to reject it
+    ///
+    /// # Errors
+    /// Returns an error if the output is not valid.
+    fn validate(&self, output: &T, context: &ParseContext) -> BoxedResult<()>;
+}
+
+impl<F, T> Validator<T> for F
+where
+    F: Fn(&T) -> BoxedResult<()>,
+{
+    fn validate(&self, output: &T, _context: &ParseContext) -> BoxedResult<()> {
+        self(output)
+    }
+}
+
+/// Parser rejecting outputs of another parser which fail a [`Validator`]
+///
+/// Failed validations are reported as a [`Rejection`], so [`File::fetch`](crate::File::fetch) keeps the last good version:
+/// the rejected `ETag` is remembered, and is not downloaded again until the object changes.
+///
+/// # Example
+///
+/// ```rust
+/// use conditional_s3_fetch::{BoxedResult, File, Lines, Validated};
+///
+/// fn not_empty(lines: &Vec<String>) -> BoxedResult<()> {
+///     if lines.is_empty() {
+///         return Err("Feed must not be empty".into());
+///     }
+///     Ok(())
+/// }
+///
+/// let parser = Validated::new(Lines, not_empty);
+/// let file = File::with_parser("bucket", "/data/feed.txt", parser);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Validated<P, V> {
+    parser: P,
+    validator: V,
+}
+
+impl<P, V> Validated<P, V> {
+    /// Check the output of `parser` with `validator`
+    pub fn new(parser: P, validator: V) -> Self {
+        Self { parser, validator }
+    }
+}
+
+impl<P, V> StreamParse for Validated<P, V>
+where
+    P: StreamParse,
+    V: Validator<P::Output>,
+{
+    type Output = P::Output;
+    type State = P::State;
+
+    fn start(&self, context: &ParseContext) -> Self::State {
+        self.parser.start(context)
+    }
+
+    fn feed(&self, state: &mut Self::State, chunk: Bytes) -> BoxedResult<()> {
+        self.parser.feed(state, chunk)
+    }
+
+    fn finish(&self, state: Self::State, context: &ParseContext) -> BoxedResult<Self::Output> {
+        let output = self.parser.finish(state, context)?;
+        self.validator
+            .validate(&output, context)
+            .map_err(Rejection)?;
+        Ok(output)
+    }
+}
+
+/// Error returned by [`Validated`] when the output fails the [`Validator`]
+///
+/// Reported by [`File::fetch`](crate::File::fetch) as [`Error::Rejected`](crate::Error::Rejected).
+#[derive(Debug)]
+pub struct Rejection(Box<dyn Error + Send + Sync>);
+
+impl Rejection {
+    /// Returns the error of the validator
+    pub fn reason(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &*self.0
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Error for Rejection {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+/// Parser returning the [`Default`] output on empty content, instead of calling the inner parser
+///
+/// Useful for formats which reject empty content, such as Json, when an empty object means "no configuration".
+///
+/// # Example
+///
+/// ```rust
+/// # #[derive(serde::Deserialize, Default)]
+/// # struct Config;
+/// use conditional_s3_fetch::{File, Json, OrDefault};
+///
+/// let file = File::<OrDefault<Json<Config>>>::unloaded("bucket", "/data/config.json");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct OrDefault<P>(P);
+
+impl<P> OrDefault<P> {
+    /// Parse non-empty content with `parser`
+    pub fn new(parser: P) -> Self {
+        Self(parser)
+    }
+}
+
+impl<P> StreamParse for OrDefault<P>
+where
+    P: StreamParse,
+    P::Output: Default,
+{
+    type Output = P::Output;
+    /// Inner parser state, and whether any content was received
+    type State = (P::State, bool);
+
+    fn start(&self, context: &ParseContext) -> Self::State {
+        (self.0.start(context), false)
+    }
+
+    fn feed(&self, (state, received): &mut Self::State, chunk: Bytes) -> BoxedResult<()> {
+        if chunk.is_empty() {
+            return Ok(());
+        }
+        *received = true;
+        self.0.feed(state, chunk)
+    }
+
+    fn finish(
+        &self,
+        (state, received): Self::State,
+        context: &ParseContext,
+    ) -> BoxedResult<Self::Output> {
+        if !received {
+            return Ok(P::Output::default());
+        }
+        self.0.finish(state, context)
+    }
+}
+
+/// Parser trying a second parser when the first one fails
+///
+/// The content is kept until the first parser finishes, so it can be parsed again by the fallback parser.
+/// Both parsers must produce the same output.
+///
+/// # Example
+///
+/// ```rust
+/// # #[derive(serde::Deserialize)]
+/// # struct Config;
+/// use conditional_s3_fetch::{Cbor, Fallback, File, Json};
+///
+/// // Migrating from Json to Cbor, while both formats may be found
+/// let file = File::<Fallback<Cbor<Config>, Json<Config>>>::unloaded("bucket", "/data/config");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Fallback<P1, P2> {
+    primary: P1,
+    fallback: P2,
+}
+
+impl<P1, P2> Fallback<P1, P2> {
+    /// Parse the content with `primary`, or with `fallback` if it fails
+    pub fn new(primary: P1, fallback: P2) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+/// State of the [`Fallback`] parser, while the content is downloaded
+pub struct FallbackState<S> {
+    primary: std::result::Result<S, Box<dyn Error + Send + Sync>>,
+    chunks: Vec<Bytes>,
+}
+
+impl<S> fmt::Debug for FallbackState<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FallbackState")
+            .field("failed", &self.primary.is_err())
+            .field("chunks", &self.chunks.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P1, P2> StreamParse for Fallback<P1, P2>
+where
+    P1: StreamParse,
+    P2: StreamParse<Output = P1::Output>,
+{
+    type Output = P1::Output;
+    type State = FallbackState<P1::State>;
+
+    fn start(&self, context: &ParseContext) -> Self::State {
+        FallbackState {
+            primary: Ok(self.primary.start(context)),
+            chunks: Vec::new(),
+        }
+    }
+
+    fn feed(&self, state: &mut Self::State, chunk: Bytes) -> BoxedResult<()> {
+        if let Ok(primary) = &mut state.primary {
+            if let Err(e) = self.primary.feed(primary, chunk.clone()) {
+                state.primary = Err(e);
+            }
+        }
+        state.chunks.push(chunk);
+        Ok(())
+    }
+
+    fn finish(&self, state: Self::State, context: &ParseContext) -> BoxedResult<Self::Output> {
+        let primary = match state.primary {
+            Ok(primary) => match self.primary.finish(primary, context) {
+                Ok(output) => return Ok(output),
+                Err(e) => e,
+            },
+            Err(e) => e,
+        };
+        tracing::debug!(error = %primary, "Primary parser failed, using fallback parser");
+
+        let mut fallback = self.fallback.start(context);
+        let result = state
+            .chunks
+            .into_iter()
+            .try_for_each(|chunk| self.fallback.feed(&mut fallback, chunk))
+            .and_then(|()| self.fallback.finish(fallback, context));
+        result.map_err(|fallback| FallbackError { primary, fallback }.into())
+    }
+}
+
+/// Error returned by [`Fallback`] when both parsers fail
+#[derive(Debug)]
+pub struct FallbackError {
+    primary: Box<dyn Error + Send + Sync>,
+    fallback: Box<dyn Error + Send + Sync>,
+}
+
+impl FallbackError {
+    /// Returns the error of the primary parser
+    pub fn primary(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &*self.primary
+    }
+
+    /// Returns the error of the fallback parser
+    pub fn fallback(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &*self.fallback
+    }
+}
+
+impl fmt::Display for FallbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (fallback: {})", self.primary, self.fallback)
+    }
+}
+
+impl Error for FallbackError {}
@@ -0,0 +1,117 @@
+//! Object information provided to parsers
+use std::collections::HashMap;
+
+use aws_sdk_s3::operation::get_object::GetObjectOutput;
+
+/// Information about the S3 object being parsed
+///
+/// Provided to [`ParseWithContext`](crate::ParseWithContext) and [`StreamParse`](crate::StreamParse) implementations,
+/// so the decoding can depend on the object key, its `Content-Type` or user metadata, such as `x-amz-meta-schema-version`.
+///
+/// # Example
+///
+/// ```rust
+/// use conditional_s3_fetch::ParseContext;
+///
+/// fn schema_version(context: &ParseContext) -> Option<u32> {
+///     context.metadata("schema-version")?.parse().ok()
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseContext {
+    bucket: String,
+    key: String,
+    etag: Option<String>,
+    version_id: Option<String>,
+    content_type: Option<String>,
+    metadata: HashMap<String, String>,
+}
+
+impl ParseContext {
+    /// Creates a context for the given object, without any response information
+    ///
+    /// Useful to call parsers directly, such as on tests.
+    pub fn new<S: Into<String>>(bucket: S, key: S) -> Self {
+        Self {
+            bucket: bucket.into(),
+            key: key.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the `ETag` of the object
+    #[must_use]
+    pub fn with_etag<S: Into<String>>(mut self, etag: S) -> Self {
+        self.etag = Some(etag.into());
+        self
+    }
+
+    /// Sets the version id of the object
+    #[must_use]
+    pub fn with_version_id<S: Into<String>>(mut self, version_id: S) -> Self {
+        self.version_id = Some(version_id.into());
+        self
+    }
+
+    /// Sets the `Content-Type` of the object
+    #[must_use]
+    pub fn with_content_type<S: Into<String>>(mut self, content_type: S) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Adds a user metadata entry, without the `x-amz-meta-` prefix
+    #[must_use]
+    pub fn with_metadata<S: Into<String>>(mut self, key: S, value: S) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    pub(crate) fn from_response(bucket: &str, key: &str, response: &GetObjectOutput) -> Self {
+        Self {
+            bucket: bucket.into(),
+            key: key.into(),
+            etag: response.e_tag.clone(),
+            version_id: response.version_id.clone(),
+            content_type: response.content_type.clone(),
+            metadata: response.metadata.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Returns the bucket of the object
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// Returns the key of the object inside the bucket
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the `ETag` of the object, if provided by S3
+    pub fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    /// Returns the version id of the object, if the bucket is versioned
+    pub fn version_id(&self) -> Option<&str> {
+        self.version_id.as_deref()
+    }
+
+    /// Returns the `Content-Type` of the object, if provided by S3
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// Returns a user metadata entry, without the `x-amz-meta-` prefix
+    ///
+    /// For example, `x-amz-meta-schema-version` is available as `metadata("schema-version")`.
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
+    }
+
+    /// Returns all the user metadata entries
+    pub fn all_metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
@@ -0,0 +1,220 @@
+//! Values computed from the content of one or more files
+//!
+//! Expensive values built from loaded files, such as a compiled regex set or a trie built from a config,
+//! only need to be built again when one of the files changes.
+//! [`Derived`] keeps the computed value with the [`Version`] of each input, and computes it again only when one of them changes.
+//!
+//! # Example
+//!
+//! ```rust
+//! # struct Index;
+//! # fn build(routes: &str, hosts: &[u8]) -> Index { Index }
+//! use conditional_s3_fetch::{Derived, File};
+//!
+//! let routes = File::<String>::unloaded("my-bucket", "/my/routes.txt");
+//! let hosts = File::<Vec<u8>>::unloaded("my-bucket", "/my/hosts.bin");
+//!
+//! let mut index = Derived::new();
+//! if let Some(new) = index.update((&routes, &hosts), |(routes, hosts)| build(routes, hosts)) {
+//!     index = new;
+//! }
+//! ```
+use std::{
+    any::Any,
+    sync::{Arc, Weak},
+};
+
+use crate::{AsyncParse, File};
+
+/// Version of the content of an input, as when a [`Derived`] value was computed
+///
+/// Content fetched from S3 is identified by its `ETag`.
+/// Content not fetched from S3, such as defaults, bootstrap and fallback content, has no `ETag`,
+/// so it is identified by its shared body instead.
+#[derive(Debug, Clone)]
+pub enum Version {
+    /// Content fetched from S3, with its `ETag`
+    Remote(String),
+    /// Content not fetched from S3, with a weak handle to its body
+    Local(Weak<dyn Any + Send + Sync>),
+}
+
+impl Version {
+    /// Returns the `ETag` of the content, or an empty string if it was not fetched from S3
+    pub fn etag(&self) -> &str {
+        match self {
+            Self::Remote(etag) => etag,
+            Self::Local(_) => "",
+        }
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Remote(etag), Self::Remote(other)) => etag == other,
+            (Self::Local(body), Self::Local(other)) => Weak::ptr_eq(body, other),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Version {}
+
+/// Inputs of a [`Derived`] value, implemented by `&File` and by tuples of up to 4 inputs
+pub trait Inputs<'a> {
+    /// Loaded content of the inputs, such as `(&A, &B)` for a tuple of files
+    type Values;
+
+    /// Returns the version of each input, or `None` if an input is not loaded
+    fn versions(&self) -> Option<Vec<Version>>;
+
+    /// Returns the loaded content of each input, or `None` if an input is not loaded
+    fn values(self) -> Option<Self::Values>;
+}
+
+impl<'a, P> Inputs<'a> for &'a File<P>
+where
+    P: AsyncParse,
+    P::Output: Send + Sync + 'static,
+{
+    type Values = &'a P::Output;
+
+    fn versions(&self) -> Option<Vec<Version>> {
+        let content = self.as_content()?;
+        let version = if content.is_remote() {
+            Version::Remote(content.etag().to_string())
+        } else {
+            let body: Weak<P::Output> = Arc::downgrade(&content.shared());
+            Version::Local(body)
+        };
+        Some(vec![version])
+    }
+
+    fn values(self) -> Option<Self::Values> {
+        self.as_content().map(|content| &**content)
+    }
+}
+
+macro_rules! impl_inputs_for_tuple {
+    ($($name:ident),+) => {
+        impl<'a, $($name),+> Inputs<'a> for ($($name,)+)
+        where
+            $($name: Inputs<'a>,)+
+        {
+            type Values = ($($name::Values,)+);
+
+            #[allow(non_snake_case)]
+            fn versions(&self) -> Option<Vec<Version>> {
+                let ($($name,)+) = self;
+                let mut versions = Vec::new();
+                $(versions.extend($name.versions()?);)+
+                Some(versions)
+            }
+
+            #[allow(non_snake_case)]
+            fn values(self) -> Option<Self::Values> {
+                let ($($name,)+) = self;
+                Some(($($name.values()?,)+))
+            }
+        }
+    };
+}
+
+impl_inputs_for_tuple!(A);
+impl_inputs_for_tuple!(A, B);
+impl_inputs_for_tuple!(A, B, C);
+impl_inputs_for_tuple!(A, B, C, D);
+
+/// Value computed from the content of one or more files, computed again only when an input changes
+///
+/// The function must be pure: its output only depends on the content of the inputs, identified by their [`Version`].
+/// Content not fetched from S3, such as defaults, bootstrap and fallback content, is identified by its shared body,
+/// so the value is computed again only when such content is replaced.
+/// Fallible computations can return a [`Result`] as the value, which is kept until an input changes.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # fn client() -> aws_sdk_s3::Client { unimplemented!() }
+/// # #[derive(serde::Deserialize)]
+/// # struct Config { patterns: Vec<String> }
+/// # struct RegexSet;
+/// # fn compile(patterns: &[String]) -> RegexSet { RegexSet }
+/// # async {
+/// # let s3_client = client();
+/// use conditional_s3_fetch::{Derived, File, Json};
+///
+/// let mut config = File::<Json<Config>>::unloaded("my-bucket", "/my/config.json");
+/// let mut patterns = Derived::new();
+///
+/// if let Ok(Some(new)) = config.fetch(&s3_client).await {
+///     config = new;
+/// }
+/// if let Some(new) = patterns.update(&config, |config| compile(&config.patterns)) {
+///     patterns = new;
+/// }
+/// # };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Derived<T> {
+    versions: Vec<Version>,
+    value: Option<T>,
+}
+
+impl<T> Default for Derived<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Derived<T> {
+    /// Creates a value which is not computed yet
+    pub fn new() -> Self {
+        Self {
+            versions: Vec::new(),
+            value: None,
+        }
+    }
+
+    /// Computes the value again if the version of an input changed
+    ///
+    /// If every input has the same [`Version`] as when the value was computed, or if an input is not loaded, it returns `None`.
+    /// Otherwise, returns a new [`Derived`] with the value computed by `compute`.
+    pub fn update<'a, I, F>(&self, inputs: I, compute: F) -> Option<Self>
+    where
+        I: Inputs<'a>,
+        F: FnOnce(I::Values) -> T,
+    {
+        let versions = inputs.versions()?;
+        if self.value.is_some() && self.versions == versions {
+            return None;
+        }
+
+        let value = compute(inputs.values()?);
+        Some(Self {
+            versions,
+            value: Some(value),
+        })
+    }
+
+    /// Returns the version of each input, as when the value was computed
+    pub fn versions(&self) -> &[Version] {
+        &self.versions
+    }
+
+    /// Returns the `ETag` of each input, as when the value was computed, or an empty string for content not fetched from S3
+    pub fn etags(&self) -> Vec<&str> {
+        self.versions.iter().map(Version::etag).collect()
+    }
+
+    /// Return the reference to the value, once computed
+    pub fn as_content(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    /// Returns the value, once computed
+    pub fn into_inner(self) -> Option<T> {
+        self.value
+    }
+}
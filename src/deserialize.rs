@@ -0,0 +1,231 @@
+//! Deserialization errors with the location of the failing field
+//!
+//! The serde based parsers report failures as a [`DeserializeError`], with the path to the field which failed,
+//! such as `routes[3].timeout_ms`, and the line and column for text formats when known.
+//!
+//! # Example
+//!
+//! ```rust
+//! use conditional_s3_fetch::{DeserializeError, Error};
+//!
+//! fn report(error: &Error) {
+//!     if let Error::ParseError(error) = error {
+//!         if let Some(error) = error.downcast_ref::<DeserializeError>() {
+//!             eprintln!("Invalid field {} at line {:?}: {}", error.path(), error.line(), error.message());
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Unknown fields are silently dropped by serde, unless the struct uses `#[serde(deny_unknown_fields)]`.
+//! Wrapping the output in [`WarnUnknown`] keeps permissive parsing, while reporting the paths of the ignored fields.
+use std::{fmt, ops::Deref};
+
+/// Error returned by the serde based parsers, with the location of the failing field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeserializeError {
+    path: String,
+    line: Option<usize>,
+    column: Option<usize>,
+    message: String,
+}
+
+impl DeserializeError {
+    #[cfg(any(
+        feature = "json",
+        feature = "simd-json",
+        feature = "cbor",
+        feature = "dotenv",
+        feature = "ini",
+        feature = "properties",
+        feature = "layered",
+        feature = "resolve",
+        feature = "json-schema"
+    ))]
+    pub(crate) fn new<E: fmt::Display>(error: &serde_path_to_error::Error<E>) -> Self {
+        Self {
+            path: error.path().to_string(),
+            line: None,
+            column: None,
+            message: error.inner().to_string(),
+        }
+    }
+
+    /// Error which is not about a specific field, such as a syntax error found before deserializing
+    #[cfg(any(feature = "json", feature = "simd-json", feature = "json-schema"))]
+    pub(crate) fn document<E: fmt::Display>(error: &E) -> Self {
+        Self {
+            path: ".".into(),
+            line: None,
+            column: None,
+            message: error.to_string(),
+        }
+    }
+
+    /// Sets the position of the error, removing it from the message where `serde_json` includes it
+    #[cfg(any(feature = "json", feature = "simd-json", feature = "json-schema"))]
+    pub(crate) fn with_position(mut self, line: usize, column: usize) -> Self {
+        let suffix = format!(" at line {line} column {column}");
+        if let Some(message) = self.message.strip_suffix(&suffix) {
+            self.message = message.to_string();
+        }
+        self.line = Some(line);
+        self.column = Some(column);
+        self
+    }
+
+    /// Sets the line of the error, such as the line of the record for line oriented formats
+    #[cfg(any(feature = "json", feature = "simd-json"))]
+    pub(crate) fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// Returns the path to the failing field, such as `routes[3].timeout_ms`, or `.` for the whole document
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the line of the error, starting at 1, for text formats
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Returns the column of the error, starting at 1, for text formats
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    /// Returns the description of the error, without its location
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)?;
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, " at line {line} column {column}"),
+            (Some(line), None) => write!(f, " at line {line}"),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for DeserializeError {}
+
+/// Output wrapper collecting the fields ignored while deserializing `T`, such as misspelled keys
+///
+/// Works with any serde based parser, such as `Json<WarnUnknown<T>>` or `Cbor<WarnUnknown<T>>`.
+/// Each ignored field is emitted as a tracing warning, and is available on the loaded content with [`WarnUnknown::ignored`].
+///
+/// It implements [`Deref`] to allow using the inner `T` methods directly.
+///
+/// # Example
+///
+/// ```rust
+/// # #[derive(serde::Deserialize)]
+/// # struct Config;
+/// use conditional_s3_fetch::{File, Json, WarnUnknown};
+///
+/// let file = File::<Json<WarnUnknown<Config>>>::unloaded("bucket", "/data/config.json");
+///
+/// if let Some(config) = file.as_content() {
+///     for path in config.ignored() {
+///         eprintln!("Unknown setting {path}");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarnUnknown<T> {
+    value: T,
+    ignored: Vec<String>,
+}
+
+impl<T> WarnUnknown<T> {
+    /// Returns the paths of the ignored fields, such as `routes.3.timout_ms`
+    pub fn ignored(&self) -> &[String] {
+        &self.ignored
+    }
+
+    /// Converts the wrapper into the deserialized value
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for WarnUnknown<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<'de, T> serde::Deserialize<'de> for WarnUnknown<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut ignored = Vec::new();
+        let value = serde_ignored::deserialize(deserializer, |path| {
+            let path = path.to_string();
+            tracing::warn!(path, "Ignored unknown field");
+            ignored.push(path);
+        })?;
+        Ok(Self { value, ignored })
+    }
+}
+
+/// Trait for the self-describing serde based parsers, to deserialize any type from their format
+///
+/// Used by parsers which read the content more than once, or as different types, such as [`Versioned`](crate::Versioned).
+/// Implemented by `Json`, `Cbor`, `DotEnv`, `Ini` and `Properties`, whatever their output type.
+pub trait SerdeFormat {
+    /// Deserialize `U` from the content, in the format of this parser
+    ///
+    /// # Errors
+    /// Returns an error if the content could not be deserialized.
+    fn from_slice<U>(bytes: &[u8]) -> crate::BoxedResult<U>
+    where
+        U: serde::de::DeserializeOwned;
+}
+
+/// Marker trait for the serde based formats keeping the type of the values, such as numbers and booleans
+///
+/// Required by constructs which read the content as an untyped value before deserializing it into the output,
+/// such as [`Layered`](crate::Layered) and [`Resolved`](crate::Resolved). Implemented by `Json` and `Cbor`.
+/// The key-value formats, `DotEnv`, `Ini` and `Properties`, only read strings, and convert them while deserializing into the output,
+/// so they do not implement it.
+pub trait TypedFormat: SerdeFormat {}
+
+/// Parser reading the content as an untyped value, with the format of `P`
+#[cfg(any(feature = "layered", feature = "resolve"))]
+pub(crate) struct Untyped<P>(std::marker::PhantomData<fn() -> P>);
+
+#[cfg(any(feature = "layered", feature = "resolve"))]
+impl<P> Default for Untyped<P> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+#[cfg(any(feature = "layered", feature = "resolve"))]
+impl<P> crate::ParseWithContext for Untyped<P>
+where
+    P: TypedFormat,
+{
+    type Output = serde_json::Value;
+
+    fn parse_with_context(
+        &self,
+        bytes: bytes::Bytes,
+        _context: &crate::ParseContext,
+    ) -> crate::BoxedResult<Self::Output> {
+        P::from_slice(&bytes)
+    }
+}
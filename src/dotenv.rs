@@ -0,0 +1,151 @@
+//! Dotenv parser implementation (feature: `dotenv`)
+//!
+//! Parser implementation to read `.env` files into a deserialized object.
+//!
+//! The supported syntax follows the common `.env` conventions:
+//! - `KEY=value` entries, optionally prefixed by `export `
+//! - Lines starting with `#` are comments, as well as ` #` after an unquoted value
+//! - Single quoted values are literal and may span multiple lines
+//! - Double quoted values may span multiple lines and support `\n`, `\r`, `\t`, `\"`, `\\` and `\$` escapes
+//!
+//! Variables are not expanded. When a key is declared more than once, the last value is used.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize)]
+//! # struct MyStruct;
+//! use conditional_s3_fetch::{File, DotEnv};
+//!
+//! let file = File::<DotEnv<MyStruct>>::unloaded("bucket", "/data/service.env");
+//! ```
+use bytes::Bytes;
+
+use crate::key_value::{self, Entries, Error, Value};
+
+/// Parser implementation to read `.env` data into a deserialized object.
+///
+/// Use [`OrderedMap`](crate::OrderedMap) as the target to keep the entries in declaration order.
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize)]
+/// # struct MyStruct;
+/// use conditional_s3_fetch::{File, DotEnv};
+///
+/// let file = File::<DotEnv<MyStruct>>::unloaded("bucket", "/data/service.env");
+/// ```
+#[derive(Debug)]
+pub struct DotEnv<T>(std::marker::PhantomData<fn() -> T>);
+
+impl<T> Default for DotEnv<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+impl<T> crate::Parse for DotEnv<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    type Output = T;
+
+    fn parse(bytes: Bytes) -> crate::BoxedResult<Self::Output> {
+        <Self as crate::SerdeFormat>::from_slice(&bytes)
+    }
+}
+
+impl<T> crate::SerdeFormat for DotEnv<T> {
+    fn from_slice<U>(bytes: &[u8]) -> crate::BoxedResult<U>
+    where
+        U: serde::de::DeserializeOwned,
+    {
+        let text = std::str::from_utf8(bytes)?;
+        let entries = read(text)?
+            .into_iter()
+            .map(|(key, value)| (key, Value::Text(value)))
+            .collect();
+        Ok(key_value::from_entries(entries)?)
+    }
+}
+
+fn read(text: &str) -> Result<Vec<(String, String)>, Error> {
+    let mut entries = Entries::default();
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line));
+
+    while let Some((number, line)) = lines.next() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, rest) = line
+            .split_once('=')
+            .ok_or_else(|| Error::syntax(number, "expected KEY=value"))?;
+
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(Error::syntax(number, format!("invalid key {key:?}")));
+        }
+
+        let rest = rest.trim_start();
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => read_quoted(quote, &rest[1..], number, &mut lines)?,
+            _ => unquoted(rest).to_string(),
+        };
+        entries.insert(key.to_string(), value);
+    }
+
+    Ok(entries.into_vec())
+}
+
+fn unquoted(value: &str) -> &str {
+    let value = match value.find(" #") {
+        Some(comment) => &value[..comment],
+        None => value,
+    };
+    value.trim()
+}
+
+fn read_quoted<'a>(
+    quote: char,
+    first: &'a str,
+    number: usize,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<String, Error> {
+    let mut value = String::new();
+    let mut current = first;
+
+    loop {
+        let mut chars = current.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                c if c == quote => return Ok(value),
+                '\\' if quote == '"' => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some(escaped @ ('"' | '\\' | '$')) => value.push(escaped),
+                    Some(other) => {
+                        value.push('\\');
+                        value.push(other);
+                    }
+                    None => value.push('\\'),
+                },
+                c => value.push(c),
+            }
+        }
+
+        match lines.next() {
+            Some((_, next)) => {
+                value.push('\n');
+                current = next;
+            }
+            None => return Err(Error::syntax(number, format!("unterminated {quote} quote"))),
+        }
+    }
+}
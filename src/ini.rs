@@ -0,0 +1,171 @@
+//! INI parser implementation (feature: `ini`)
+//!
+//! Parser implementation to read `.ini` files into a deserialized object.
+//!
+//! The supported syntax is:
+//! - `[section]` headers, each one deserialized as a nested struct or map
+//! - `key = value` or `key: value` entries. Entries before the first section are top-level fields
+//! - Lines starting with `;` or `#` are comments
+//! - Double quoted values support `\n`, `\r`, `\t`, `\"` and `\\` escapes
+//! - Indented lines following an entry continue its value on a new line
+//!
+//! Sections declared more than once are merged, and the last value of a repeated key is used.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize)]
+//! # struct MyStruct;
+//! use conditional_s3_fetch::{File, Ini};
+//!
+//! let file = File::<Ini<MyStruct>>::unloaded("bucket", "/data/settings.ini");
+//! ```
+use bytes::Bytes;
+
+use crate::key_value::{self, Entries, Error, Value};
+
+/// Parser implementation to read INI data into a deserialized object.
+///
+/// Use [`OrderedMap`](crate::OrderedMap) as the target to keep the sections and entries in declaration order.
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize)]
+/// # struct MyStruct;
+/// use conditional_s3_fetch::{File, Ini};
+///
+/// let file = File::<Ini<MyStruct>>::unloaded("bucket", "/data/settings.ini");
+/// ```
+#[derive(Debug)]
+pub struct Ini<T>(std::marker::PhantomData<fn() -> T>);
+
+impl<T> Default for Ini<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+impl<T> crate::Parse for Ini<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    type Output = T;
+
+    fn parse(bytes: Bytes) -> crate::BoxedResult<Self::Output> {
+        <Self as crate::SerdeFormat>::from_slice(&bytes)
+    }
+}
+
+impl<T> crate::SerdeFormat for Ini<T> {
+    fn from_slice<U>(bytes: &[u8]) -> crate::BoxedResult<U>
+    where
+        U: serde::de::DeserializeOwned,
+    {
+        let text = std::str::from_utf8(bytes)?;
+        Ok(key_value::from_entries(read(text)?)?)
+    }
+}
+
+enum Position {
+    Global,
+    Section(String),
+}
+
+fn read(text: &str) -> Result<Vec<(String, Value)>, Error> {
+    let mut globals: Entries<String> = Entries::default();
+    let mut sections: Entries<Entries<String>> = Entries::default();
+    let mut position = Position::Global;
+    let mut last_key: Option<String> = None;
+
+    for (index, raw) in text.lines().enumerate() {
+        let number = index + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+
+        let entries = match &position {
+            Position::Global => &mut globals,
+            Position::Section(name) => sections
+                .get_mut(name)
+                .expect("current section is always registered"),
+        };
+
+        if raw.starts_with(char::is_whitespace) {
+            if let Some(value) = last_key.as_deref().and_then(|key| entries.get_mut(key)) {
+                value.push('\n');
+                value.push_str(line);
+                continue;
+            }
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .ok_or_else(|| Error::syntax(number, "unterminated section header"))?
+                .trim()
+                .to_string();
+            if sections.get_mut(&name).is_none() {
+                sections.insert(name.clone(), Entries::default());
+            }
+            position = Position::Section(name);
+            last_key = None;
+            continue;
+        }
+
+        let separator = line
+            .find(['=', ':'])
+            .ok_or_else(|| Error::syntax(number, "expected key = value"))?;
+        let key = line[..separator].trim();
+        if key.is_empty() {
+            return Err(Error::syntax(number, "empty key"));
+        }
+        let value = read_value(line[separator + 1..].trim(), number)?;
+
+        entries.insert(key.to_string(), value);
+        last_key = Some(key.to_string());
+    }
+
+    let globals = globals
+        .into_vec()
+        .into_iter()
+        .map(|(key, value)| (key, Value::Text(value)));
+    let sections = sections
+        .into_vec()
+        .into_iter()
+        .map(|(name, entries)| (name, Value::Section(entries.into_vec())));
+    Ok(globals.chain(sections).collect())
+}
+
+fn read_value(value: &str, number: usize) -> Result<String, Error> {
+    let Some(quoted) = value.strip_prefix('"') else {
+        return Ok(value.to_string());
+    };
+
+    let mut unescaped = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                return match chars.as_str().trim() {
+                    "" => Ok(unescaped),
+                    trailing => Err(Error::syntax(
+                        number,
+                        format!("unexpected {trailing:?} after quoted value"),
+                    )),
+                }
+            }
+            '\\' => match chars.next() {
+                Some('n') => unescaped.push('\n'),
+                Some('r') => unescaped.push('\r'),
+                Some('t') => unescaped.push('\t'),
+                Some(other) => unescaped.push(other),
+                None => unescaped.push('\\'),
+            },
+            c => unescaped.push(c),
+        }
+    }
+
+    Err(Error::syntax(number, "unterminated quote"))
+}
@@ -0,0 +1,198 @@
+//! Placeholder interpolation of the content before parsing
+//!
+//! Shared files may contain placeholders resolved on each host, such as `${REGION}`.
+//! The [`Interpolated`] parser expands them from the process environment, or from a user-supplied map,
+//! before handing the content to the inner parser.
+//!
+//! # Syntax
+//!
+//! - `${NAME}` is replaced by the value of `NAME`, failing with [`InterpolationError::Undefined`] if it is not defined.
+//! - `${NAME:-default}` is replaced by the value of `NAME`, or by `default` if it is not defined or empty.
+//! - `$$` is replaced by a single `$`, to write a literal `${`.
+//! - Any other `$` is kept as is, such as in `"$ref"`.
+//!
+//! Names are made of ASCII letters, digits and `_`. Defaults are inserted as is, without expanding them again.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize)]
+//! # struct Config;
+//! use conditional_s3_fetch::{File, Interpolated, Json};
+//!
+//! // Placeholders resolved from the process environment
+//! let file = File::<Interpolated<Json<Config>>>::unloaded("my-bucket", "/my/config.json");
+//! ```
+use std::{collections::HashMap, fmt};
+
+use bytes::Bytes;
+
+use crate::{BoxedResult, ParseContext, StreamParse};
+
+/// Parser expanding `${NAME}` placeholders before parsing the content with another parser
+///
+/// Placeholders are resolved from the process environment by default, when the file is fetched.
+/// Use [`Interpolated::with_variables`] to resolve them from a map instead.
+///
+/// The content is buffered until it is downloaded, as placeholders may span several chunks.
+///
+/// # Example
+///
+/// ```rust
+/// # #[derive(serde::Deserialize)]
+/// # struct Config;
+/// use std::collections::HashMap;
+/// use conditional_s3_fetch::{File, Interpolated, Json};
+///
+/// let variables = HashMap::from([("REGION".to_string(), "eu-west-1".to_string())]);
+/// let parser = Interpolated::new(Json::<Config>::default()).with_variables(variables);
+/// let file = File::with_parser("my-bucket", "/my/config.json", parser);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Interpolated<P> {
+    parser: P,
+    variables: Option<HashMap<String, String>>,
+}
+
+impl<P> Interpolated<P> {
+    /// Expand placeholders from the process environment, then parse with `parser`
+    pub fn new(parser: P) -> Self {
+        Self {
+            parser,
+            variables: None,
+        }
+    }
+
+    /// Resolve placeholders from `variables` only, instead of the process environment
+    #[must_use]
+    pub fn with_variables(mut self, variables: HashMap<String, String>) -> Self {
+        self.variables = Some(variables);
+        self
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        match &self.variables {
+            Some(variables) => variables.get(name).cloned(),
+            None => std::env::var(name).ok(),
+        }
+    }
+
+    /// Expand every placeholder of `content`
+    fn expand(&self, content: &[u8]) -> Result<Vec<u8>, InterpolationError> {
+        let mut output = Vec::with_capacity(content.len());
+        let mut line = 1;
+        let mut rest = content;
+
+        while let Some(position) = rest.iter().position(|&b| b == b'$' || b == b'\n') {
+            output.extend_from_slice(&rest[..position]);
+            match (rest[position], rest.get(position + 1)) {
+                (b'\n', _) => {
+                    line += 1;
+                    output.push(b'\n');
+                    rest = &rest[position + 1..];
+                }
+                (_, Some(b'$')) => {
+                    output.push(b'$');
+                    rest = &rest[position + 2..];
+                }
+                (_, Some(b'{')) => {
+                    let placeholder = &rest[position + 2..];
+                    let end = placeholder
+                        .iter()
+                        .position(|&b| b == b'}' || b == b'\n')
+                        .filter(|&end| placeholder[end] == b'}')
+                        .ok_or(InterpolationError::Unterminated { line })?;
+                    let value = self.resolve(&placeholder[..end], line)?;
+                    output.extend_from_slice(value.as_bytes());
+                    rest = &placeholder[end + 1..];
+                }
+                _ => {
+                    output.push(b'$');
+                    rest = &rest[position + 1..];
+                }
+            }
+        }
+        output.extend_from_slice(rest);
+        Ok(output)
+    }
+
+    /// Resolve the content of a placeholder, between `${` and `}`
+    fn resolve(&self, placeholder: &[u8], line: usize) -> Result<String, InterpolationError> {
+        let placeholder = String::from_utf8_lossy(placeholder);
+        let (name, default) = match placeholder.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&*placeholder, None),
+        };
+
+        let valid =
+            !name.is_empty() && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_');
+        if !valid {
+            return Err(InterpolationError::InvalidName {
+                name: name.to_string(),
+                line,
+            });
+        }
+
+        match (self.lookup(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => Ok(default.to_string()),
+            (Some(value), _) => Ok(value),
+            (None, Some(default)) => Ok(default.to_string()),
+            (None, None) => Err(InterpolationError::Undefined {
+                name: name.to_string(),
+                line,
+            }),
+        }
+    }
+}
+
+impl<P> StreamParse for Interpolated<P>
+where
+    P: StreamParse,
+{
+    type Output = P::Output;
+    /// Content received so far
+    type State = Vec<u8>;
+
+    fn start(&self, _context: &ParseContext) -> Self::State {
+        Vec::new()
+    }
+
+    fn feed(&self, state: &mut Self::State, chunk: Bytes) -> BoxedResult<()> {
+        state.extend_from_slice(&chunk);
+        Ok(())
+    }
+
+    fn finish(&self, state: Self::State, context: &ParseContext) -> BoxedResult<Self::Output> {
+        let content = self.expand(&state)?;
+        let mut inner = self.parser.start(context);
+        self.parser.feed(&mut inner, Bytes::from(content))?;
+        self.parser.finish(inner, context)
+    }
+}
+
+/// Error returned by [`Interpolated`] when a placeholder could not be expanded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterpolationError {
+    /// The variable is not defined, and the placeholder has no default
+    Undefined { name: String, line: usize },
+    /// The placeholder name is empty, or has characters other than ASCII letters, digits and `_`
+    InvalidName { name: String, line: usize },
+    /// The placeholder is not closed by `}` on the same line
+    Unterminated { line: usize },
+}
+
+impl fmt::Display for InterpolationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Undefined { name, line } => {
+                write!(f, "Undefined variable {name} at line {line}")
+            }
+            Self::InvalidName { name, line } => {
+                write!(f, "Invalid variable name \"{name}\" at line {line}")
+            }
+            Self::Unterminated { line } => write!(f, "Unterminated placeholder at line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for InterpolationError {}
@@ -11,7 +11,9 @@
 //!
 //! let file = File::<Json<MyStruct>>::unloaded("bucket", "/data/key.Json");
 //! ```
-use bytes::{Buf, Bytes};
+use bytes::Bytes;
+
+use crate::{lines::LineBuffer, DeserializeError};
 
 #[cfg(all(feature = "json", feature = "simd-json"))]
 compile_error!("Cannot enable both json and simd-json features");
@@ -28,7 +30,13 @@ compile_error!("Cannot enable both json and simd-json features");
 /// let file = File::<Json<MyStruct>>::unloaded("bucket", "/data/key.Json");
 /// ```
 #[derive(Debug)]
-pub struct Json<T>(std::marker::PhantomData<T>);
+pub struct Json<T>(std::marker::PhantomData<fn() -> T>);
+
+impl<T> Default for Json<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
 
 impl<T> crate::Parse for Json<T>
 where
@@ -36,15 +44,351 @@ where
 {
     type Output = T;
 
-    #[cfg(feature = "json")]
     fn parse(bytes: Bytes) -> crate::BoxedResult<Self::Output> {
-        let buffer = bytes.reader();
-        Ok(serde_json::from_reader(buffer)?)
+        Ok(from_slice(&bytes)?)
     }
+}
 
-    #[cfg(feature = "simd-json")]
-    fn parse(bytes: Bytes) -> crate::BoxedResult<Self::Output> {
-        let buffer = bytes.reader();
-        Ok(simd_json::from_reader(buffer)?)
+impl<T> crate::SerdeFormat for Json<T> {
+    fn from_slice<U>(bytes: &[u8]) -> crate::BoxedResult<U>
+    where
+        U: serde::de::DeserializeOwned,
+    {
+        Ok(from_slice(bytes)?)
+    }
+}
+
+impl<T> crate::TypedFormat for Json<T> {}
+
+/// Parser implementation to read only the value at a [Json pointer](https://www.rfc-editor.org/rfc/rfc6901), such as `/services/payments`
+///
+/// Only the addressed value is deserialized into `T`, and the rest of the document is skipped.
+/// The content is still downloaded and buffered completely.
+///
+/// The document is always read with `serde_json`, even with the `simd-json` feature, so the skipped values are not allocated.
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize)]
+/// # struct Payments;
+/// use conditional_s3_fetch::{File, JsonAt};
+///
+/// let parser = JsonAt::<Payments>::new("/services/payments").expect("Invalid pointer");
+/// let file = File::with_parser("bucket", "/data/services.json", parser);
+/// ```
+pub struct JsonAt<T> {
+    pointer: String,
+    tokens: Vec<String>,
+    output: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> JsonAt<T> {
+    /// Creates a parser reading the value at `pointer`, or the whole document if it is empty
+    ///
+    /// Tokens are unescaped as in the Json pointer specification, `~1` for `/` and `~0` for `~`.
+    ///
+    /// # Errors
+    /// Returns an error if the pointer is not empty and does not start with `/`.
+    pub fn new<S: Into<String>>(pointer: S) -> crate::BoxedResult<Self> {
+        let pointer = pointer.into();
+        let tokens = match pointer.strip_prefix('/') {
+            Some(tokens) => tokens
+                .split('/')
+                .map(|token| token.replace("~1", "/").replace("~0", "~"))
+                .collect(),
+            None if pointer.is_empty() => Vec::new(),
+            None => {
+                return Err(format!("Invalid Json pointer {pointer:?}: must start with /").into())
+            }
+        };
+        Ok(Self {
+            pointer,
+            tokens,
+            output: std::marker::PhantomData,
+        })
+    }
+
+    /// Returns the pointer to the value read by this parser
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+}
+
+impl<T> std::fmt::Debug for JsonAt<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonAt")
+            .field("pointer", &self.pointer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> crate::ParseWithContext for JsonAt<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    type Output = T;
+
+    fn parse_with_context(
+        &self,
+        bytes: Bytes,
+        _context: &crate::ParseContext,
+    ) -> crate::BoxedResult<Self::Output> {
+        let seed = Pointer {
+            tokens: &self.tokens,
+            output: std::marker::PhantomData,
+        };
+        from_slice_seed(&bytes, seed)?.ok_or_else(|| {
+            DeserializeError::document(&format!("No value at {}", self.pointer)).into()
+        })
+    }
+}
+
+/// Seed following the remaining pointer `tokens`, skipping every other value
+struct Pointer<'p, T> {
+    tokens: &'p [String],
+    output: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<'de, T> serde::de::DeserializeSeed<'de> for Pointer<'_, T>
+where
+    T: serde::Deserialize<'de>,
+{
+    type Value = Option<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if self.tokens.is_empty() {
+            return T::deserialize(deserializer).map(Some);
+        }
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, T> serde::de::Visitor<'de> for Pointer<'_, T>
+where
+    T: serde::Deserialize<'de>,
+{
+    type Value = Option<T>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "an object or an array containing {:?}", self.tokens[0])
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let (token, tokens) = (&self.tokens[0], &self.tokens[1..]);
+        let mut found = None;
+        while let Some(key) = map.next_key::<std::borrow::Cow<'de, str>>()? {
+            if found.is_none() && key == token.as_str() {
+                let seed = Pointer {
+                    tokens,
+                    output: std::marker::PhantomData,
+                };
+                found = Some(map.next_value_seed(seed)?);
+            } else {
+                map.next_value::<serde::de::IgnoredAny>()?;
+            }
+        }
+        Ok(found.flatten())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let (token, tokens) = (&self.tokens[0], &self.tokens[1..]);
+        let mut found = None;
+        if let Ok(index) = token.parse::<usize>() {
+            for _ in 0..index {
+                if seq.next_element::<serde::de::IgnoredAny>()?.is_none() {
+                    return Ok(None);
+                }
+            }
+            let seed = Pointer {
+                tokens,
+                output: std::marker::PhantomData,
+            };
+            found = seq.next_element_seed(seed)?.flatten();
+        }
+        while seq.next_element::<serde::de::IgnoredAny>()?.is_some() {}
+        Ok(found)
+    }
+}
+
+/// Deserialize a Json document, reporting the path to the failing field with its line and column
+#[cfg(feature = "json")]
+fn from_slice<'a, T>(bytes: &'a [u8]) -> Result<T, DeserializeError>
+where
+    T: serde::Deserialize<'a>,
+{
+    from_slice_seed(bytes, std::marker::PhantomData)
+}
+
+/// Deserialize a Json document with a seed, such as a [`JsonAt`] pointer
+fn from_slice_seed<'a, S>(bytes: &'a [u8], seed: S) -> Result<S::Value, DeserializeError>
+where
+    S: serde::de::DeserializeSeed<'a>,
+{
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let mut track = serde_path_to_error::Track::new();
+    let value = seed
+        .deserialize(serde_path_to_error::Deserializer::new(
+            &mut deserializer,
+            &mut track,
+        ))
+        .map_err(|error| {
+            let error = serde_path_to_error::Error::new(track.path(), error);
+            with_position(DeserializeError::new(&error), error.inner())
+        })?;
+    deserializer
+        .end()
+        .map_err(|error| with_position(DeserializeError::document(&error), &error))?;
+    Ok(value)
+}
+
+fn with_position(error: DeserializeError, source: &serde_json::Error) -> DeserializeError {
+    if source.line() == 0 {
+        return error;
+    }
+    error.with_position(source.line(), source.column())
+}
+
+/// Deserialize a Json document, reporting the path to the failing field
+#[cfg(feature = "simd-json")]
+fn from_slice<T>(bytes: &[u8]) -> Result<T, DeserializeError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let mut buffer = bytes.to_vec();
+    let mut deserializer =
+        simd_json::Deserializer::from_slice(&mut buffer).map_err(|e| syntax_error(bytes, &e))?;
+    serde_path_to_error::deserialize(&mut deserializer).map_err(|e| DeserializeError::new(&e))
+}
+
+/// Convert a `simd-json` syntax error, with its line and column when known
+///
+/// `simd-json` only exposes the byte offset in its message, as `at character 12 ('x')`.
+/// The position is kept only when the reported character is found at that offset,
+/// as some errors, such as invalid escapes, report an unrelated offset.
+#[cfg(feature = "simd-json")]
+fn syntax_error(bytes: &[u8], error: &simd_json::Error) -> DeserializeError {
+    let message = error.to_string();
+    let position = message
+        .rsplit_once(" at character ")
+        .and_then(|(kind, location)| {
+            let (index, character) = location.split_once(" ('")?;
+            let index = index.parse::<usize>().ok()?;
+            let character = character.strip_suffix("')")?;
+            bytes[index..]
+                .starts_with(character.as_bytes())
+                .then_some((kind, index))
+        });
+    let Some((kind, index)) = position else {
+        return DeserializeError::document(&message);
+    };
+
+    let line_start = bytes[..index]
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |newline| newline + 1);
+    let line = 1 + bytes[..index].iter().filter(|byte| **byte == b'\n').count();
+    DeserializeError::document(&kind).with_position(line, index - line_start + 1)
+}
+
+/// Streaming parser implementation to read newline delimited Json (NDJSON) into a list of deserialized objects.
+///
+/// Each line is deserialized as soon as it is downloaded, so only the current line is buffered in memory.
+/// Blank lines are skipped. Errors report the line of the failing record.
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize)]
+/// # struct MyStruct;
+/// use conditional_s3_fetch::{File, NdJson};
+///
+/// let file = File::<NdJson<MyStruct>>::unloaded("bucket", "/data/events.ndjson");
+/// ```
+#[derive(Debug)]
+pub struct NdJson<T>(std::marker::PhantomData<fn() -> T>);
+
+impl<T> Default for NdJson<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+impl<T> crate::StreamParse for NdJson<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    type Output = Vec<T>;
+    /// Line reader, parsed items, and the number of lines read
+    type State = (LineBuffer, Vec<T>, usize);
+
+    fn start(&self, _context: &crate::ParseContext) -> Self::State {
+        (LineBuffer::default(), Vec::new(), 0)
+    }
+
+    fn feed(
+        &self,
+        (buffer, items, lines): &mut Self::State,
+        chunk: Bytes,
+    ) -> crate::BoxedResult<()> {
+        buffer.push(&chunk, |line| push_line(items, lines, line))
+    }
+
+    fn finish(
+        &self,
+        (buffer, mut items, mut lines): Self::State,
+        _context: &crate::ParseContext,
+    ) -> crate::BoxedResult<Self::Output> {
+        buffer.finish(|line| push_line(&mut items, &mut lines, line))?;
+        Ok(items)
+    }
+}
+
+fn push_line<T>(items: &mut Vec<T>, lines: &mut usize, line: &[u8]) -> crate::BoxedResult<()>
+where
+    T: serde::de::DeserializeOwned,
+{
+    *lines += 1;
+    if line.trim_ascii().is_empty() {
+        return Ok(());
+    }
+
+    let item = from_slice(line).map_err(|e| e.with_line(*lines))?;
+    items.push(item);
+    Ok(())
+}
+
+/// Borrowing parser implementation, keeping string slices into the fetched content (feature: `borrowed`)
+///
+/// Only available with the `json` feature, as `simd-json` requires a mutable buffer to parse.
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize, yoke::Yokeable)]
+/// # struct MyStruct<'a> { name: &'a str }
+/// use conditional_s3_fetch::{Borrowed, File, Json};
+///
+/// let file = File::<Borrowed<Json<MyStruct<'static>>>>::unloaded("bucket", "/data/key.json");
+/// ```
+#[cfg(all(feature = "json", feature = "borrowed"))]
+impl<T> crate::ParseBorrowed for Json<T>
+where
+    T: for<'a> yoke::Yokeable<'a>,
+    for<'a> <T as yoke::Yokeable<'a>>::Output: serde::Deserialize<'a>,
+{
+    type Output = T;
+
+    fn parse_borrowed(bytes: &[u8]) -> crate::BoxedResult<<T as yoke::Yokeable<'_>>::Output> {
+        Ok(from_slice(bytes)?)
     }
 }
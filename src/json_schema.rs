@@ -0,0 +1,194 @@
+//! Json Schema validating parser implementation. (feature `json-schema`)
+//!
+//! Parser implementation to validate Json data against a [Json Schema](https://json-schema.org/) before deserializing it,
+//! catching documents which deserialize but violate the intended shape, such as unknown enum values or out-of-range numbers.
+//!
+//! The schema can be embedded at compile time with [`include_str!`], or generated from the output type with `schemars` (feature `schemars`).
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize)]
+//! # struct MyStruct;
+//! use conditional_s3_fetch::{File, JsonSchema};
+//!
+//! let schema = r#"{ "type": "object", "required": ["name"] }"#;
+//! let parser = JsonSchema::<MyStruct>::from_json(schema).expect("Invalid schema");
+//! let file = File::with_parser("bucket", "/data/key.json", parser);
+//! ```
+use std::{fmt, marker::PhantomData};
+
+use bytes::Bytes;
+
+use crate::DeserializeError;
+
+/// Parser implementation to validate Json data against a schema, then read it into a deserialized object.
+///
+/// The document is rejected with [`SchemaViolations`] if it does not match the schema, listing every violation.
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize)]
+/// # struct MyStruct;
+/// use conditional_s3_fetch::{File, JsonSchema};
+///
+/// let schema = serde_json::json!({ "type": "object", "required": ["name"] });
+/// let parser = JsonSchema::<MyStruct>::new(&schema).expect("Invalid schema");
+/// let file = File::with_parser("bucket", "/data/key.json", parser);
+/// ```
+pub struct JsonSchema<T> {
+    validator: jsonschema::Validator,
+    output: PhantomData<fn() -> T>,
+}
+
+impl<T> JsonSchema<T> {
+    /// Creates a parser validating with the given schema
+    ///
+    /// # Errors
+    /// Returns an error if the schema is not valid.
+    pub fn new(schema: &serde_json::Value) -> crate::BoxedResult<Self> {
+        let validator = jsonschema::validator_for(schema).map_err(|e| e.to_string())?;
+        Ok(Self {
+            validator,
+            output: PhantomData,
+        })
+    }
+
+    /// Creates a parser validating with the given schema document, such as one embedded with [`include_str!`]
+    ///
+    /// # Errors
+    /// Returns an error if the document is not valid Json, or not a valid schema.
+    pub fn from_json(schema: &str) -> crate::BoxedResult<Self> {
+        Self::new(&serde_json::from_str(schema)?)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl<T> JsonSchema<T>
+where
+    T: schemars::JsonSchema,
+{
+    /// Creates a parser validating with the schema generated from `T` (feature `schemars`)
+    ///
+    /// # Panics
+    /// Panics if the generated schema is not valid.
+    pub fn generated() -> Self {
+        let schema = schemars::schema_for!(T);
+        Self::new(schema.as_value()).expect("Generated schema should be valid")
+    }
+}
+
+/// Validates with the schema generated from `T` (feature `schemars`)
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize, schemars::JsonSchema)]
+/// # struct MyStruct;
+/// use conditional_s3_fetch::{File, JsonSchema};
+///
+/// let file = File::<JsonSchema<MyStruct>>::unloaded("bucket", "/data/key.json");
+/// ```
+#[cfg(feature = "schemars")]
+impl<T> Default for JsonSchema<T>
+where
+    T: schemars::JsonSchema,
+{
+    fn default() -> Self {
+        Self::generated()
+    }
+}
+
+impl<T> fmt::Debug for JsonSchema<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonSchema").finish_non_exhaustive()
+    }
+}
+
+impl<T> crate::ParseWithContext for JsonSchema<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    type Output = T;
+
+    fn parse_with_context(
+        &self,
+        bytes: Bytes,
+        _context: &crate::ParseContext,
+    ) -> crate::BoxedResult<Self::Output> {
+        let document: serde_json::Value = serde_json::from_slice(&bytes).map_err(|error| {
+            DeserializeError::document(&error).with_position(error.line(), error.column())
+        })?;
+
+        let violations: Vec<_> = self
+            .validator
+            .iter_errors(&document)
+            .map(|error| Violation {
+                instance_path: error.instance_path.to_string(),
+                schema_path: error.schema_path.to_string(),
+                message: error.to_string(),
+            })
+            .collect();
+        if !violations.is_empty() {
+            return Err(SchemaViolations(violations).into());
+        }
+
+        serde_path_to_error::deserialize(document)
+            .map_err(|error| DeserializeError::new(&error).into())
+    }
+}
+
+/// Error returned by [`JsonSchema`] when the document does not match the schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolations(Vec<Violation>);
+
+impl SchemaViolations {
+    /// Returns every violation found in the document
+    pub fn violations(&self) -> &[Violation] {
+        &self.0
+    }
+}
+
+impl fmt::Display for SchemaViolations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Document does not match the schema:")?;
+        for violation in &self.0 {
+            write!(f, " {violation};")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SchemaViolations {}
+
+/// Single schema violation found in a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    instance_path: String,
+    schema_path: String,
+    message: String,
+}
+
+impl Violation {
+    /// Returns the Json pointer to the invalid value in the document, such as `/routes/3/timeout_ms`
+    pub fn instance_path(&self) -> &str {
+        &self.instance_path
+    }
+
+    /// Returns the Json pointer to the failed keyword in the schema, such as `/properties/port/minimum`
+    pub fn schema_path(&self) -> &str {
+        &self.schema_path
+    }
+
+    /// Returns the description of the violation
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\": {}", self.instance_path, self.message)
+    }
+}
@@ -0,0 +1,276 @@
+//! Shared deserializer for the key-value based parsers (features: `dotenv`, `ini`, `properties`)
+//!
+//! Line oriented configuration files only carry text values.
+//! This module turns the parsed entries into a [`serde::Deserializer`] which converts the text into the types requested by the target struct,
+//! such as numbers, booleans, unit enums or comma separated sequences.
+//!
+//! When the target type is not known ahead of time, [`OrderedMap`] keeps the entries in the same order they were declared on the file.
+//!
+//! # Example
+//!
+//! ```rust
+//! use conditional_s3_fetch::{File, DotEnv, OrderedMap};
+//!
+//! let file = File::<DotEnv<OrderedMap>>::unloaded("bucket", "/data/service.env");
+//! ```
+use std::fmt;
+
+use serde::de::{self, value::MapDeserializer, IntoDeserializer, Visitor};
+
+/// Error raised while reading or deserializing key-value files
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Syntax error on line {line}: {message}")]
+    Syntax { line: usize, message: String },
+    #[error("{0}")]
+    Message(String),
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Message(msg.to_string())
+    }
+}
+
+impl Error {
+    pub(crate) fn syntax<S: Into<String>>(line: usize, message: S) -> Self {
+        Self::Syntax {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+/// Value read from a key-value file
+///
+/// `Text` holds a single entry, while `Section` holds the entries of an INI section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    Text(String),
+    #[cfg_attr(not(feature = "ini"), allow(dead_code))]
+    Section(Vec<(String, String)>),
+}
+
+/// Collects entries, keeping the position of the first declaration and the value of the last one.
+#[derive(Debug, Default)]
+pub(crate) struct Entries<V>(Vec<(String, V)>);
+
+impl<V> Entries<V> {
+    pub(crate) fn insert(&mut self, key: String, value: V) {
+        match self.0.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.0.push((key, value)),
+        }
+    }
+
+    #[cfg_attr(not(feature = "ini"), allow(dead_code))]
+    pub(crate) fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        self.0
+            .iter_mut()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value)
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<(String, V)> {
+        self.0
+    }
+}
+
+/// Deserialize `T` from an ordered list of entries
+pub(crate) fn from_entries<T>(entries: Vec<(String, Value)>) -> Result<T, crate::DeserializeError>
+where
+    T: de::DeserializeOwned,
+{
+    let deserializer = MapDeserializer::<_, Error>::new(entries.into_iter());
+    serde_path_to_error::deserialize(deserializer).map_err(|e| crate::DeserializeError::new(&e))
+}
+
+impl Value {
+    fn into_text<'de, V: Visitor<'de>>(self, visitor: &V) -> Result<String, Error> {
+        match self {
+            Self::Text(text) => Ok(text),
+            Self::Section(_) => Err(de::Error::invalid_type(de::Unexpected::Map, visitor)),
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+macro_rules! deserialize_from_str {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                let text = self.into_text(&visitor)?;
+                let parsed = text
+                    .trim()
+                    .parse()
+                    .map_err(|e| Error::Message(format!("invalid value {text:?}: {e}")))?;
+                visitor.$visit(parsed)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Self::Text(text) => visitor.visit_string(text),
+            Self::Section(entries) => {
+                let entries = entries.into_iter().map(|(k, v)| (k, Self::Text(v)));
+                visitor.visit_map(MapDeserializer::new(entries))
+            }
+        }
+    }
+
+    deserialize_from_str! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.into_text(&visitor)? {
+            text if text.is_empty() => visitor.visit_unit(),
+            text => Err(de::Error::invalid_value(
+                de::Unexpected::Str(&text),
+                &visitor,
+            )),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Sequences are read from comma separated values, such as `a, b, c`
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let text = self.into_text(&visitor)?;
+        let items = text
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| Self::Text(item.to_string()));
+        visitor.visit_seq(de::value::SeqDeserializer::new(items))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let text = self.into_text(&visitor)?;
+        visitor.visit_enum(IntoDeserializer::<Error>::into_deserializer(text))
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit_struct tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Map which keeps the entries in the order they were declared on the file
+///
+/// Useful to read key-value files without declaring a struct upfront.
+///
+/// # Example
+///
+/// ```rust
+/// use conditional_s3_fetch::{File, Ini, OrderedMap};
+///
+/// let file = File::<Ini<OrderedMap<OrderedMap>>>::unloaded("bucket", "/data/settings.ini");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderedMap<V = String>(Vec<(String, V)>);
+
+impl<V> Default for OrderedMap<V> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<V> OrderedMap<V> {
+    /// Returns the value of the given key, if present
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.0
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Iterates over the entries in declaration order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &V)> {
+        self.0.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Returns the amount of entries
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no entries
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Converts the map into the inner list of entries
+    pub fn into_inner(self) -> Vec<(String, V)> {
+        self.0
+    }
+}
+
+impl<'de, V> de::Deserialize<'de> for OrderedMap<V>
+where
+    V: de::Deserialize<'de>,
+{
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OrderedMapVisitor<V>(std::marker::PhantomData<V>);
+
+        impl<'de, V> Visitor<'de> for OrderedMapVisitor<V>
+        where
+            V: de::Deserialize<'de>,
+        {
+            type Value = OrderedMap<V>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(OrderedMap(entries))
+            }
+        }
+
+        deserializer.deserialize_map(OrderedMapVisitor(std::marker::PhantomData))
+    }
+}
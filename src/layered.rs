@@ -0,0 +1,237 @@
+//! Layered configuration, merging several files into one value. (feature `layered`)
+//!
+//! Configuration is often split into a base file and overrides, such as `base.json`, `env/prod.json` and `region/eu-west-1.json`.
+//! [`Layered`] fetches every layer, deep-merges them in order with [Json merge patch](https://www.rfc-editor.org/rfc/rfc7386) semantics,
+//! and deserializes the merged value into the output of the parser.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize)]
+//! # struct Config;
+//! use conditional_s3_fetch::{Json, Layered};
+//!
+//! let config = Layered::<Json<Config>>::new("my-bucket")
+//!     .layer("base.json")
+//!     .optional_layer("env/prod.json")
+//!     .optional_layer("region/eu-west-1.json");
+//! ```
+use std::{fmt, sync::Arc};
+
+use serde_json::Value;
+
+use crate::{deserialize::Untyped, DeserializeError, Error, File, Parse, TypedFormat};
+
+/// Set of files merged in order into a single value, refetching only the layers which changed
+///
+/// Each layer is read as an untyped value with the format of `P`, such as `Json<Config>` or `Cbor<Config>`.
+/// Only formats keeping the type of the values are supported, see [`TypedFormat`].
+/// Objects are merged recursively, later layers overriding earlier ones, and a `null` value removes the key.
+/// Any other value replaces the previous one, including arrays.
+///
+/// Optional layers may be missing, and are merged again once they are created.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # fn client() -> aws_sdk_s3::Client { unimplemented!() }
+/// # #[derive(serde::Deserialize)]
+/// # struct Config;
+/// # async {
+/// # let s3_client = client();
+/// use conditional_s3_fetch::{Json, Layered};
+///
+/// let mut config = Layered::<Json<Config>>::new("my-bucket")
+///     .layer("base.json")
+///     .optional_layer("env/prod.json");
+///
+/// match config.fetch(&s3_client).await {
+///     Ok(Some(new)) => config = new,
+///     Ok(None) => println!("No modification"),
+///     Err(e) => eprintln!("Error: {}", e),
+/// }
+/// # };
+/// ```
+pub struct Layered<P>
+where
+    P: Parse + TypedFormat,
+{
+    bucket: String,
+    layers: Vec<Layer<P>>,
+    merged: Option<P::Output>,
+}
+
+struct Layer<P>
+where
+    P: TypedFormat,
+{
+    file: Arc<File<Untyped<P>>>,
+    optional: bool,
+}
+
+impl<P> Clone for Layer<P>
+where
+    P: TypedFormat,
+{
+    fn clone(&self) -> Self {
+        Self {
+            file: Arc::clone(&self.file),
+            optional: self.optional,
+        }
+    }
+}
+
+impl<P> Layered<P>
+where
+    P: Parse + TypedFormat + 'static,
+    P::Output: serde::de::DeserializeOwned,
+{
+    /// Creates an empty set of layers on the given bucket
+    pub fn new<S: Into<String>>(bucket: S) -> Self {
+        Self {
+            bucket: bucket.into(),
+            layers: Vec::new(),
+            merged: None,
+        }
+    }
+
+    /// Adds a layer which must exist, overriding the previous layers
+    #[must_use]
+    pub fn layer<S: Into<String>>(self, key: S) -> Self {
+        self.with_layer(key.into(), false)
+    }
+
+    /// Adds a layer which may be missing, overriding the previous layers
+    #[must_use]
+    pub fn optional_layer<S: Into<String>>(self, key: S) -> Self {
+        self.with_layer(key.into(), true)
+    }
+
+    fn with_layer(mut self, key: String, optional: bool) -> Self {
+        let file = File::with_parser(self.bucket.clone(), key, Untyped::default());
+        self.layers.push(Layer {
+            file: Arc::new(file),
+            optional,
+        });
+        self
+    }
+
+    /// Returns the bucket of the layers
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// Returns the keys of the layers, in merge order
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|layer| layer.file.path())
+    }
+
+    /// Returns the keys of the layers currently merged, skipping the missing optional layers
+    pub fn loaded_keys(&self) -> impl Iterator<Item = &str> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.file.as_content().is_some())
+            .map(|layer| layer.file.path())
+    }
+
+    /// Return the reference to the merged value, once fetched
+    pub fn as_content(&self) -> Option<&P::Output> {
+        self.merged.as_ref()
+    }
+
+    /// Returns the merged value, once fetched
+    pub fn into_inner(self) -> Option<P::Output> {
+        self.merged
+    }
+
+    /// Attempt to fetch every layer from S3 using `If-None-Match` header
+    ///
+    /// Only the layers which changed are downloaded again.
+    /// If no layer has been modified, created or deleted, it returns `None`.
+    /// Otherwise, returns a new [`Layered`] with the layers merged again.
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if a layer could not be fetched or parsed, if a required layer is missing,
+    /// or if the merged value could not be deserialized.
+    #[tracing::instrument(skip_all)]
+    pub async fn fetch(&self, s3_client: &aws_sdk_s3::Client) -> crate::Result<Option<Self>> {
+        let mut layers = self.layers.clone();
+        let mut modified = self.merged.is_none();
+
+        for layer in &mut layers {
+            match layer.file.fetch(s3_client).await {
+                Ok(Some(file)) => {
+                    layer.file = Arc::new(file);
+                    modified = true;
+                }
+                Ok(None) => {}
+                Err(Error::NotFound { .. }) if layer.optional => {
+                    if layer.file.as_content().is_some() {
+                        let file = File::with_parser(
+                            self.bucket.clone(),
+                            layer.file.path().to_string(),
+                            Untyped::default(),
+                        );
+                        layer.file = Arc::new(file);
+                        modified = true;
+                    }
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        if !modified {
+            return Ok(None);
+        }
+
+        let mut merged = Value::Null;
+        for layer in &layers {
+            if let Some(value) = layer.file.as_content() {
+                merge(&mut merged, value);
+            }
+        }
+        let merged = serde_path_to_error::deserialize(merged)
+            .map_err(|e| Error::ParseError(Box::new(DeserializeError::new(&e))))?;
+
+        Ok(Some(Self {
+            bucket: self.bucket.clone(),
+            layers,
+            merged: Some(merged),
+        }))
+    }
+}
+
+impl<P> fmt::Debug for Layered<P>
+where
+    P: Parse + TypedFormat,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<_> = self.layers.iter().map(|layer| layer.file.path()).collect();
+        f.debug_struct("Layered")
+            .field("bucket", &self.bucket)
+            .field("keys", &keys)
+            .field("loaded", &self.merged.is_some())
+            .finish()
+    }
+}
+
+/// Apply `patch` over `target`, with Json merge patch semantics
+fn merge(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
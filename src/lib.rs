@@ -1,7 +1,13 @@
 #![doc = include_str!("../README.md")]
 use aws_sdk_s3::{error::SdkError, operation::get_object::GetObjectOutput};
 use bytes::Bytes;
-use std::{fmt, marker::PhantomData, ops::Deref};
+use sha2::{Digest, Sha256};
+use std::{
+    fmt,
+    ops::Deref,
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -13,20 +19,120 @@ pub enum Error {
     ParseError(Box<dyn std::error::Error + Send>),
     #[error("Unabled to convert an unloaded file to a loaded file")]
     UnabledToLoad,
+    #[error("Parsing task was dropped before completing")]
+    ParseAborted,
+    #[error("Local Read Error: {0}")]
+    LocalReadError(#[from] std::io::Error),
+    #[error("Object not found: {bucket}/{key}")]
+    NotFound { bucket: String, key: String },
+    #[error("Rejected version {etag}: {rejection}")]
+    Rejected {
+        etag: String,
+        #[source]
+        rejection: combinators::Rejection,
+    },
 }
 
 type Result<T> = std::result::Result<T, Error>;
 
+pub mod body;
+pub use body::Body;
+
+pub mod context;
+pub use context::ParseContext;
+
+pub mod offload;
+#[cfg(feature = "tokio")]
+pub use offload::SpawnBlocking;
+pub use offload::{Completion, Executor, Offload, Task};
+
+pub mod lines;
+pub use lines::Lines;
+
+#[cfg(feature = "serde")]
+pub mod deserialize;
+#[cfg(feature = "serde")]
+pub use deserialize::{DeserializeError, SerdeFormat, TypedFormat, WarnUnknown};
+
+#[cfg(feature = "serde")]
+pub mod versioned;
+#[cfg(feature = "serde")]
+pub use versioned::{Migrations, VersionError, VersionMarker, Versioned};
+
+pub mod combinators;
+pub use combinators::{Fallback, Map, Mapper, OrDefault, Rejection, Validated, Validator};
+
+pub mod interpolate;
+pub use interpolate::Interpolated;
+
+pub mod derived;
+pub use derived::Derived;
+
 #[cfg(any(feature = "json", feature = "simd-json"))]
 pub mod json;
 #[cfg(any(feature = "json", feature = "simd-json"))]
-pub use json::Json;
+pub use json::{Json, JsonAt, NdJson};
+
+#[cfg(feature = "layered")]
+pub mod layered;
+#[cfg(feature = "layered")]
+pub use layered::Layered;
+
+#[cfg(feature = "resolve")]
+pub mod resolve;
+#[cfg(feature = "resolve")]
+pub use resolve::Resolved;
+
+#[cfg(feature = "json-schema")]
+pub mod json_schema;
+#[cfg(feature = "json-schema")]
+pub use json_schema::JsonSchema;
 
 #[cfg(feature = "cbor")]
 pub mod cbor;
 #[cfg(feature = "cbor")]
 pub use cbor::Cbor;
 
+#[cfg(feature = "borrowed")]
+pub mod borrowed;
+#[cfg(feature = "borrowed")]
+pub use borrowed::{Borrowed, ParseBorrowed, Yoked};
+
+#[cfg(any(feature = "dotenv", feature = "ini", feature = "properties"))]
+pub mod key_value;
+#[cfg(any(feature = "dotenv", feature = "ini", feature = "properties"))]
+pub use key_value::OrderedMap;
+
+#[cfg(feature = "dotenv")]
+pub mod dotenv;
+#[cfg(feature = "dotenv")]
+pub use dotenv::DotEnv;
+
+#[cfg(feature = "ini")]
+pub mod ini;
+#[cfg(feature = "ini")]
+pub use ini::Ini;
+
+#[cfg(feature = "properties")]
+pub mod properties;
+#[cfg(feature = "properties")]
+pub use properties::Properties;
+
+#[cfg(feature = "bincode")]
+pub mod bincode;
+#[cfg(feature = "bincode")]
+pub use self::bincode::Bincode;
+
+#[cfg(feature = "postcard")]
+pub mod postcard;
+#[cfg(feature = "postcard")]
+pub use self::postcard::Postcard;
+
+#[cfg(feature = "rkyv")]
+pub mod rkyv;
+#[cfg(feature = "rkyv")]
+pub use self::rkyv::{ArchivedContent, Rkyv};
+
 // Needs https://github.com/alex-dixon/serde_edn published on crates.io
 // Meanwhile use the git dependency and implement the trait on your project
 // #[cfg(feature = "edn")]
@@ -38,6 +144,9 @@ pub use cbor::Cbor;
 ///
 /// It implements [`Deref`] to allow using the inner `T` methods directly.
 ///
+/// The content is shared with an [`Arc`], so clones are cheap and keep the same version,
+/// even after a newer version of the file is loaded.
+///
 /// # Example
 /// ```rust,no_run
 /// # fn data() -> Content<String> { unimplemented!() }
@@ -46,17 +155,55 @@ pub use cbor::Cbor;
 /// let content: Content<String> = data();
 /// assert_eq!(content.len(), 13);
 /// ```
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq)]
 pub struct Content<T> {
     etag: String,
-    body: T,
+    body: Arc<T>,
 }
 
 impl<T> Content<T> {
-    /// Converts the container struct into the inner value
-    pub fn into_inner(self) -> T {
+    /// Returns the `ETag` of the loaded version, empty for content not fetched from S3
+    pub fn etag(&self) -> &str {
+        &self.etag
+    }
+
+    /// Returns false for content not fetched from S3, such as defaults, bootstrap and fallback content
+    pub fn is_remote(&self) -> bool {
+        !self.etag.is_empty()
+    }
+
+    /// Returns a shared handle to the inner value, which stays valid after a newer version is loaded
+    pub fn shared(&self) -> Arc<T> {
+        Arc::clone(&self.body)
+    }
+
+    /// Converts the container struct into a shared handle to the inner value
+    pub fn into_shared(self) -> Arc<T> {
         self.body
     }
+
+    /// Converts the container struct into the inner value
+    ///
+    /// Returns the content back if the value is still shared, such as by a clone of this content.
+    /// Use [`Content::into_shared`] to keep a shared handle instead.
+    ///
+    /// # Errors
+    /// Returns the content unchanged if the value is still shared.
+    pub fn into_inner(self) -> std::result::Result<T, Self> {
+        Arc::try_unwrap(self.body).map_err(|body| Self {
+            etag: self.etag,
+            body,
+        })
+    }
+}
+
+impl<T> Clone for Content<T> {
+    fn clone(&self) -> Self {
+        Self {
+            etag: self.etag.clone(),
+            body: Arc::clone(&self.body),
+        }
+    }
 }
 
 impl<T> Deref for Content<T> {
@@ -66,32 +213,188 @@ impl<T> Deref for Content<T> {
     }
 }
 
+/// Change applied to a [`File`] by [`File::refresh`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refresh<T> {
+    /// The file has not been modified, or the new version was skipped
+    Unchanged,
+    /// The file was not loaded, or only had content not fetched from S3, and is now loaded
+    FirstLoad,
+    /// A new version of the file is now loaded
+    Updated {
+        /// `ETag` of the replaced version
+        previous_etag: String,
+        /// Content of the replaced version, which can be dropped if not needed
+        previous: Content<T>,
+    },
+    /// The object was deleted, following [`DeletionPolicy::MarkDeleted`] or [`DeletionPolicy::Fallback`]
+    Deleted {
+        /// Content of the replaced version, if it was loaded
+        previous: Option<Content<T>>,
+    },
+}
+
+impl<T> Refresh<T> {
+    /// Returns true if the file changed, with a new version now loaded or the object deleted from S3
+    ///
+    /// A [`Refresh::Deleted`] counts as a change; match on it to tell a deletion apart from a new version.
+    pub fn is_changed(&self) -> bool {
+        !matches!(self, Self::Unchanged)
+    }
+}
+
+/// What to do when the object of a [`File`] is deleted, set with [`File::with_deletion_policy`]
+///
+/// Once the file is [`File::Deleted`], a new upload of the object is loaded like the first version.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub enum DeletionPolicy<T> {
+    /// Keep serving the loaded content, returning [`Error::NotFound`] on each fetch
+    #[default]
+    KeepContent,
+    /// Switch to [`File::Deleted`], without content
+    MarkDeleted,
+    /// Switch to [`File::Deleted`], serving the given content with an empty `ETag`
+    Fallback(Arc<T>),
+}
+
+impl<T> Clone for DeletionPolicy<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::KeepContent => Self::KeepContent,
+            Self::MarkDeleted => Self::MarkDeleted,
+            Self::Fallback(body) => Self::Fallback(Arc::clone(body)),
+        }
+    }
+}
+
 /// Container struct to hold S3 file metadata to be fetched
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq)]
 pub struct UnloadedFile<P>
 where
-    P: Parse,
+    P: AsyncParse,
 {
     bucket: String,
     path: String,
-    parser: PhantomData<P>,
+    parser: Arc<P>,
+    hash_content: bool,
+    deletion: DeletionPolicy<P::Output>,
 }
 
 /// Container struct to hold S3 file metadata and parsed content
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq)]
 pub struct LoadedFile<P>
 where
-    P: Parse,
+    P: AsyncParse,
 {
     bucket: String,
     path: String,
     inner: Content<P::Output>,
-    parser: PhantomData<P>,
+    parser: Arc<P>,
+    /// `ETag` of the latest version which was not loaded, such as a rejected version or identical content
+    latest: EtagCell,
+    /// `ETag` of the last version with invalid content or rejected by a [`Validator`]
+    rejected: EtagCell,
+    hash_content: bool,
+    hash: Option<ContentHash>,
+    deletion: DeletionPolicy<P::Output>,
 }
 
+/// Container struct to hold S3 file metadata of a deleted object, with the fallback content if any
+#[derive(Debug, Eq, PartialEq)]
+pub struct DeletedFile<P>
+where
+    P: AsyncParse,
+{
+    bucket: String,
+    path: String,
+    fallback: Option<Content<P::Output>>,
+    parser: Arc<P>,
+    hash_content: bool,
+    deletion: DeletionPolicy<P::Output>,
+}
+
+impl<P> Clone for UnloadedFile<P>
+where
+    P: AsyncParse,
+{
+    fn clone(&self) -> Self {
+        Self {
+            bucket: self.bucket.clone(),
+            path: self.path.clone(),
+            parser: Arc::clone(&self.parser),
+            hash_content: self.hash_content,
+            deletion: self.deletion.clone(),
+        }
+    }
+}
+
+/// Clones share the parser and the parsed content, without copying them
+impl<P> Clone for LoadedFile<P>
+where
+    P: AsyncParse,
+{
+    fn clone(&self) -> Self {
+        Self {
+            bucket: self.bucket.clone(),
+            path: self.path.clone(),
+            inner: self.inner.clone(),
+            parser: Arc::clone(&self.parser),
+            latest: self.latest.clone(),
+            rejected: self.rejected.clone(),
+            hash_content: self.hash_content,
+            hash: self.hash,
+            deletion: self.deletion.clone(),
+        }
+    }
+}
+
+impl<P> Clone for DeletedFile<P>
+where
+    P: AsyncParse,
+{
+    fn clone(&self) -> Self {
+        Self {
+            bucket: self.bucket.clone(),
+            path: self.path.clone(),
+            fallback: self.fallback.clone(),
+            parser: Arc::clone(&self.parser),
+            hash_content: self.hash_content,
+            deletion: self.deletion.clone(),
+        }
+    }
+}
+
+/// `ETag` updated without creating a new version of the file, kept to avoid downloading it again
+#[derive(Debug, Default)]
+struct EtagCell(Mutex<Option<String>>);
+
+impl EtagCell {
+    fn get(&self) -> Option<String> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn set(&self, etag: Option<String>) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = etag;
+    }
+}
+
+impl Clone for EtagCell {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.get()))
+    }
+}
+
+impl PartialEq for EtagCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl Eq for EtagCell {}
+
 /// Container struct that holds either a reference to an unloaded file or a loaded file with it's content parsed.
 ///
-/// Given a `P: Parse` implementation, it will parse the content of the file when it's loaded.
+/// Given a `P: Parse` (or `P: StreamParse`, `P: AsyncParse`) implementation, it will parse the content of the file when it's loaded.
 ///
 /// # Example
 /// ```rust,no_run
@@ -125,23 +428,40 @@ where
 /// ```
 pub enum File<P>
 where
-    P: Parse,
+    P: AsyncParse,
 {
     /// Reference to an unloaded file on S3
     Unloaded(UnloadedFile<P>),
     /// Reference to a loaded file on S3 with parsed content
     Loaded(LoadedFile<P>),
+    /// Reference to a deleted file on S3, following [`DeletionPolicy::MarkDeleted`] or [`DeletionPolicy::Fallback`]
+    Deleted(DeletedFile<P>),
+}
+
+/// Clones share the parser and the parsed content, without copying them
+impl<P> Clone for File<P>
+where
+    P: AsyncParse,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::Unloaded(file) => Self::Unloaded(file.clone()),
+            Self::Loaded(file) => Self::Loaded(file.clone()),
+            Self::Deleted(file) => Self::Deleted(file.clone()),
+        }
+    }
 }
 
 impl<P> PartialEq for File<P>
 where
-    P: Parse + PartialEq,
+    P: AsyncParse + PartialEq,
     P::Output: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Unloaded(l0), Self::Unloaded(r0)) => l0 == r0,
             (Self::Loaded(l0), Self::Loaded(r0)) => l0 == r0,
+            (Self::Deleted(l0), Self::Deleted(r0)) => l0 == r0,
             _ => false,
         }
     }
@@ -149,21 +469,21 @@ where
 
 impl<P> Eq for File<P>
 where
-    P: Parse + PartialEq,
+    P: AsyncParse + PartialEq,
     P::Output: Eq,
 {
 }
 
 impl<P> File<P>
 where
-    P: Parse,
+    P: AsyncParse,
 {
     /// Returns the path of the file inside the bucket
     pub fn path(&self) -> &str {
         match self {
-            Self::Unloaded(UnloadedFile { path, .. }) | Self::Loaded(LoadedFile { path, .. }) => {
-                path
-            }
+            Self::Unloaded(UnloadedFile { path, .. })
+            | Self::Loaded(LoadedFile { path, .. })
+            | Self::Deleted(DeletedFile { path, .. }) => path,
         }
     }
 
@@ -171,56 +491,117 @@ where
     pub fn bucket(&self) -> &str {
         match self {
             Self::Unloaded(UnloadedFile { bucket, .. })
-            | Self::Loaded(LoadedFile { bucket, .. }) => bucket,
+            | Self::Loaded(LoadedFile { bucket, .. })
+            | Self::Deleted(DeletedFile { bucket, .. }) => bucket,
         }
     }
 
-    /// Return the reference to the inner content if the file is [`File::loaded`]
+    /// Returns the `ETag` of the last version with invalid content or rejected by a [`Validated`] parser, if the file is [`File::loaded`]
+    ///
+    /// While set, this version is not downloaded again, and the loaded content is kept.
+    pub fn rejected_etag(&self) -> Option<String> {
+        match self {
+            Self::Unloaded(_) | Self::Deleted(_) => None,
+            Self::Loaded(LoadedFile { rejected, .. }) => rejected.get(),
+        }
+    }
+
+    /// Returns the parser used to parse the file content
+    pub fn parser(&self) -> &P {
+        match self {
+            Self::Unloaded(UnloadedFile { parser, .. })
+            | Self::Loaded(LoadedFile { parser, .. })
+            | Self::Deleted(DeletedFile { parser, .. }) => parser,
+        }
+    }
+
+    /// Returns true if the object was deleted, following [`DeletionPolicy::MarkDeleted`] or [`DeletionPolicy::Fallback`]
+    pub fn is_deleted(&self) -> bool {
+        matches!(self, Self::Deleted(_))
+    }
+
+    /// Return the reference to the inner content if the file is [`File::loaded`], or the fallback content if deleted
     pub fn as_content(&self) -> Option<&Content<P::Output>> {
         match self {
             Self::Unloaded(_) => None,
             Self::Loaded(file) => Some(&file.inner),
+            Self::Deleted(file) => file.fallback.as_ref(),
+        }
+    }
+
+    /// Returns the inner value of the parsed struct if the file is [`File::loaded`], or the fallback content if deleted
+    ///
+    /// Drops all the metadata related to the S3 File reference.
+    ///
+    /// # Errors
+    /// Returns the file back if it has no content, or if the value is still shared, such as by a clone of the file or of its [`Content`];
+    /// use [`File::into_shared`] to keep a shared handle instead.
+    #[allow(clippy::result_large_err)] // The file is given back as is, as with `Arc::try_unwrap`
+    pub fn into_inner(self) -> std::result::Result<P::Output, Self> {
+        match self {
+            Self::Unloaded(file) => Err(Self::Unloaded(file)),
+            Self::Loaded(file) => match file.inner.into_inner() {
+                Ok(value) => Ok(value),
+                Err(inner) => Err(Self::Loaded(LoadedFile { inner, ..file })),
+            },
+            Self::Deleted(DeletedFile {
+                fallback: Some(fallback),
+                bucket,
+                path,
+                parser,
+                hash_content,
+                deletion,
+            }) => fallback.into_inner().map_err(|fallback| {
+                Self::Deleted(DeletedFile {
+                    bucket,
+                    path,
+                    fallback: Some(fallback),
+                    parser,
+                    hash_content,
+                    deletion,
+                })
+            }),
+            Self::Deleted(file) => Err(Self::Deleted(file)),
         }
     }
 
-    /// Returns the inner value of the parsed struct if the file is [`File::loaded`]
+    /// Returns a shared handle to the inner value if the file is [`File::loaded`], or the fallback content if deleted
     ///
     /// Drops all the metadata related to the S3 File reference.
-    pub fn into_inner(self) -> Option<P::Output> {
+    pub fn into_shared(self) -> Option<Arc<P::Output>> {
         match self {
             Self::Unloaded { .. } => None,
-            Self::Loaded(file) => Some(file.inner.into_inner()),
+            Self::Loaded(file) => Some(file.inner.into_shared()),
+            Self::Deleted(file) => file.fallback.map(Content::into_shared),
         }
     }
 }
 
 impl<P> fmt::Debug for File<P>
 where
-    P: Parse,
+    P: AsyncParse,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parser = std::any::type_name::<P>();
         match self {
-            Self::Unloaded(UnloadedFile {
-                bucket,
-                path,
-                parser,
-                ..
-            }) => {
-                write!(f, "File#Unloaded<{bucket}:{path} parser={parser:?}>")
+            Self::Unloaded(UnloadedFile { bucket, path, .. }) => {
+                write!(f, "File#Unloaded<{bucket}:{path} parser={parser}>")
             }
             Self::Loaded(LoadedFile {
                 bucket,
                 path,
                 inner,
-                parser,
                 ..
             }) => {
                 write!(
                     f,
-                    "File#Loaded<{bucket}:{path} parser={parser:?} etag={}>",
+                    "File#Loaded<{bucket}:{path} parser={parser} etag={}>",
                     &inner.etag
                 )
             }
+            Self::Deleted(DeletedFile { bucket, path, .. }) => {
+                write!(f, "File#Deleted<{bucket}:{path} parser={parser}>")
+            }
         }
     }
 }
@@ -241,6 +622,63 @@ pub trait Parse {
     fn parse(bytes: bytes::Bytes) -> BoxedResult<Self::Output>;
 }
 
+/// Trait to parse the [`File`] content using information about the S3 object
+///
+/// Like [`Parse`], but also receives a [`ParseContext`], with the object key, `ETag`, `Content-Type` and user metadata.
+/// Every [`Parse`] implementation is also a [`ParseWithContext`], ignoring the context.
+///
+/// Parsing happens on a parser instance, so it can hold configuration, such as decryption keys or compiled schemas.
+/// Use [`File::with_parser`] to provide the instance, or [`File::unloaded`] to use its [`Default`] value.
+///
+/// # Example
+///
+/// ```rust
+/// use conditional_s3_fetch::{BoxedResult, File, ParseContext, ParseWithContext};
+///
+/// struct Text {
+///     content_type: &'static str,
+/// }
+///
+/// impl ParseWithContext for Text {
+///     type Output = String;
+///
+///     fn parse_with_context(&self, data: bytes::Bytes, context: &ParseContext) -> BoxedResult<String> {
+///         match context.content_type() {
+///             Some(content_type) if content_type == self.content_type => Ok(String::from_utf8(data.to_vec())?),
+///             other => Err(format!("Unexpected content type {other:?} on {}", context.key()).into()),
+///         }
+///     }
+/// }
+///
+/// let parser = Text { content_type: "text/plain" };
+/// let file = File::with_parser("my-bucket", "/my/path.txt", parser);
+/// ```
+pub trait ParseWithContext {
+    type Output;
+
+    /// Parse the file content from S3, with information about the object
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if the content could not be parsed.
+    fn parse_with_context(&self, bytes: Bytes, context: &ParseContext)
+        -> BoxedResult<Self::Output>;
+}
+
+impl<P> ParseWithContext for P
+where
+    P: Parse,
+{
+    type Output = P::Output;
+
+    fn parse_with_context(
+        &self,
+        bytes: Bytes,
+        _context: &ParseContext,
+    ) -> BoxedResult<Self::Output> {
+        P::parse(bytes)
+    }
+}
+
 /// Parse a [`File`] content as a [`String`] struct
 ///
 /// # Example
@@ -289,9 +727,171 @@ impl Parse for Vec<u8> {
     }
 }
 
+/// Trait to parse the [`File`] content while it is downloaded from S3
+///
+/// [`Parse`] implementations receive the whole content at once, which requires buffering the entire object in memory.
+/// Implementations of this trait receive each chunk as soon as it arrives instead,
+/// allowing large objects, such as line oriented feeds, to be parsed with bounded memory.
+///
+/// Every [`Parse`] and [`ParseWithContext`] implementation is also a [`StreamParse`], buffering the content until it is complete.
+///
+/// # Example
+///
+/// ```rust
+/// use bytes::Bytes;
+/// use conditional_s3_fetch::{BoxedResult, File, ParseContext, StreamParse};
+///
+/// /// Counts the bytes of the file, without keeping the content in memory
+/// #[derive(Default)]
+/// struct ByteCount;
+///
+/// impl StreamParse for ByteCount {
+///     type Output = usize;
+///     type State = usize;
+///
+///     fn start(&self, _context: &ParseContext) -> usize {
+///         0
+///     }
+///
+///     fn feed(&self, count: &mut usize, chunk: Bytes) -> BoxedResult<()> {
+///         *count += chunk.len();
+///         Ok(())
+///     }
+///
+///     fn finish(&self, count: usize, _context: &ParseContext) -> BoxedResult<usize> {
+///         Ok(count)
+///     }
+/// }
+///
+/// let file = File::<ByteCount>::unloaded("my-bucket", "/my/large-file.dat");
+/// ```
+pub trait StreamParse {
+    type Output;
+    /// State kept while the content is downloaded
+    type State;
+
+    /// Create the initial state, before any content is received
+    fn start(&self, context: &ParseContext) -> Self::State;
+
+    /// Parse the next chunk of content from S3
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if the content could not be parsed.
+    fn feed(&self, state: &mut Self::State, chunk: Bytes) -> BoxedResult<()>;
+
+    /// Produce the output once all the content has been received
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if the content could not be parsed.
+    fn finish(&self, state: Self::State, context: &ParseContext) -> BoxedResult<Self::Output>;
+}
+
+/// Content buffered for [`ParseWithContext`] implementations, until the download is complete
+#[derive(Debug, Default)]
+pub struct Buffered(Vec<Bytes>);
+
+impl Buffered {
+    fn into_bytes(mut self) -> Bytes {
+        if self.0.len() == 1 {
+            return self.0.remove(0);
+        }
+        let len = self.0.iter().map(Bytes::len).sum();
+        let mut buffer = bytes::BytesMut::with_capacity(len);
+        for chunk in self.0 {
+            buffer.extend_from_slice(&chunk);
+        }
+        buffer.freeze()
+    }
+}
+
+impl<P> StreamParse for P
+where
+    P: ParseWithContext,
+{
+    type Output = P::Output;
+    type State = Buffered;
+
+    fn start(&self, _context: &ParseContext) -> Self::State {
+        Buffered::default()
+    }
+
+    fn feed(&self, state: &mut Self::State, chunk: Bytes) -> BoxedResult<()> {
+        state.0.push(chunk);
+        Ok(())
+    }
+
+    fn finish(&self, state: Self::State, context: &ParseContext) -> BoxedResult<Self::Output> {
+        self.parse_with_context(state.into_bytes(), context)
+    }
+}
+
+/// Trait to parse the [`File`] content with a parser which needs to await during decoding
+///
+/// Useful when decoding depends on I/O, such as fetching a referenced schema, unwrapping a data key, or opening a database,
+/// so the parser does not have to block the executor.
+/// The parser receives the [`Body`] of the object, to read it chunk by chunk or as a whole.
+///
+/// Every [`Parse`], [`ParseWithContext`] and [`StreamParse`] implementation is also an [`AsyncParse`].
+///
+/// # Example
+///
+/// ```rust
+/// use conditional_s3_fetch::{AsyncParse, Body, BoxedResult, File, ParseContext};
+///
+/// # async fn unwrap_key(key_id: &str) -> BoxedResult<u8> { Ok(42) }
+/// /// Decodes content obfuscated with a key referenced by the object metadata
+/// #[derive(Default)]
+/// struct Obfuscated;
+///
+/// impl AsyncParse for Obfuscated {
+///     type Output = Vec<u8>;
+///
+///     async fn parse_async(&self, body: Body, context: &ParseContext) -> BoxedResult<Vec<u8>> {
+///         let key = unwrap_key(context.metadata("key-id").unwrap_or_default()).await?;
+///         let content = body.bytes().await?;
+///         Ok(content.iter().map(|byte| byte ^ key).collect())
+///     }
+/// }
+///
+/// let file = File::<Obfuscated>::unloaded("my-bucket", "/my/secret.dat");
+/// ```
+pub trait AsyncParse {
+    type Output;
+
+    /// Parse the file content from S3, awaiting as needed
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if the content could not be read or parsed.
+    fn parse_async(
+        &self,
+        body: Body,
+        context: &ParseContext,
+    ) -> impl std::future::Future<Output = BoxedResult<Self::Output>> + Send;
+}
+
+impl<P> AsyncParse for P
+where
+    P: StreamParse + Sync,
+    P::State: Send,
+{
+    type Output = P::Output;
+
+    async fn parse_async(
+        &self,
+        mut body: Body,
+        context: &ParseContext,
+    ) -> BoxedResult<Self::Output> {
+        let mut state = self.start(context);
+        while let Some(chunk) = body.try_next().await? {
+            self.feed(&mut state, chunk)?;
+        }
+        self.finish(state, context)
+    }
+}
+
 impl<P> File<P>
 where
-    P: Parse,
+    P: AsyncParse,
 {
     /// Creates a reference to an unloaded file on S3
     ///
@@ -321,14 +921,231 @@ where
     /// }
     /// # };
     /// ```
-    pub fn unloaded<S: Into<String>>(bucket: S, path: S) -> Self {
+    pub fn unloaded<S: Into<String>>(bucket: S, path: S) -> Self
+    where
+        P: Default,
+    {
+        Self::with_parser(bucket, path, P::default())
+    }
+
+    /// Creates a reference to an unloaded file on S3, parsed by the given parser instance
+    ///
+    /// Useful for parsers holding configuration, such as decryption keys or compiled schemas.
+    /// The parser is shared by every version of the file returned by `fetch`.
+    ///
+    ///  ## Example
+    ///
+    /// ```rust
+    /// use conditional_s3_fetch::{BoxedResult, File, Parse, ParseContext, ParseWithContext};
+    ///
+    /// struct Prefixed(&'static str);
+    ///
+    /// impl ParseWithContext for Prefixed {
+    ///     type Output = String;
+    ///
+    ///     fn parse_with_context(&self, data: bytes::Bytes, context: &ParseContext) -> BoxedResult<String> {
+    ///         Ok(format!("{}{}", self.0, String::parse_with_context(&String::new(), data, context)?))
+    ///     }
+    /// }
+    ///
+    /// let file = File::with_parser("my-bucket", "/my/path.txt", Prefixed("> "));
+    /// assert_eq!(file.parser().0, "> ");
+    /// ```
+    pub fn with_parser<S: Into<String>>(bucket: S, path: S, parser: P) -> Self {
         Self::Unloaded(UnloadedFile {
             bucket: bucket.into(),
             path: path.into(),
-            parser: PhantomData,
+            parser: Arc::new(parser),
+            hash_content: false,
+            deletion: DeletionPolicy::default(),
         })
     }
 
+    /// Creates a reference to a file on S3, loaded with the given default content until it is fetched
+    ///
+    /// Useful for services which must start even when S3 is not reachable.
+    /// The default content has an empty `ETag`, and [`Content::is_remote`] returns false for it.
+    /// The first successful fetch replaces it unconditionally, without the `If-None-Match` header.
+    ///
+    ///  ## Example
+    ///
+    /// ```rust
+    /// use conditional_s3_fetch::File;
+    ///
+    /// let file = File::<String>::with_default("my-bucket", "/my/path.txt", "fallback".into());
+    /// let content = file.as_content().expect("Default content is loaded");
+    /// assert!(!content.is_remote());
+    /// ```
+    pub fn with_default<S: Into<String>>(bucket: S, path: S, value: P::Output) -> Self
+    where
+        P: Default,
+    {
+        Self::unloaded(bucket, path).with_default_content(value)
+    }
+
+    /// Sets the content served until the file is fetched, such as for a file created with [`File::with_parser`]
+    ///
+    /// Behaves like [`File::with_default`]. A file already loaded from S3 is kept as is.
+    ///
+    ///  ## Example
+    ///
+    /// ```rust
+    /// # #[derive(serde::Deserialize, Default)]
+    /// # struct Payments;
+    /// use conditional_s3_fetch::{File, JsonAt};
+    ///
+    /// let parser = JsonAt::<Payments>::new("/services/payments").expect("Invalid pointer");
+    /// let file = File::with_parser("my-bucket", "/my/services.json", parser)
+    ///     .with_default_content(Payments::default());
+    /// ```
+    #[must_use]
+    pub fn with_default_content(self, value: P::Output) -> Self {
+        if self.as_content().is_some_and(Content::is_remote) {
+            return self;
+        }
+        self.with_content(String::new(), value, None)
+    }
+
+    /// Creates a reference to a file on S3, loaded with the content of a local file until it is fetched
+    ///
+    /// The local file is parsed like the object, such as a copy shipped with the service.
+    /// As with [`File::with_default`], the content has an empty `ETag`, and the first successful fetch replaces it unconditionally.
+    /// The local file is read with blocking IO, which is meant for initialization.
+    ///
+    ///  ## Example
+    ///
+    /// ```rust,no_run
+    /// # async {
+    /// use conditional_s3_fetch::File;
+    ///
+    /// let file = File::<String>::bootstrap_from_path("my-bucket", "/my/path.txt", "config/path.txt")
+    ///     .await
+    ///     .expect("Failed to read the bootstrap file");
+    /// # };
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`Error::LocalReadError`] if the local file could not be read,
+    /// or an [`Error`] if its content could not be parsed.
+    pub async fn bootstrap_from_path<S: Into<String>>(
+        bucket: S,
+        path: S,
+        local_path: impl AsRef<Path>,
+    ) -> Result<Self>
+    where
+        P: Default,
+    {
+        Self::unloaded(bucket, path)
+            .with_bootstrap_from_path(local_path)
+            .await
+    }
+
+    /// Loads the content of a local file until the file is fetched, parsed with the parser of this file
+    ///
+    /// Behaves like [`File::bootstrap_from_path`], for files created with [`File::with_parser`].
+    /// A file already loaded from S3 is kept as is, without reading the local file.
+    ///
+    ///  ## Example
+    ///
+    /// ```rust,no_run
+    /// # #[derive(serde::Deserialize)]
+    /// # struct Payments;
+    /// # async {
+    /// use conditional_s3_fetch::{File, JsonAt};
+    ///
+    /// let parser = JsonAt::<Payments>::new("/services/payments").expect("Invalid pointer");
+    /// let file = File::with_parser("my-bucket", "/my/services.json", parser)
+    ///     .with_bootstrap_from_path("config/services.json")
+    ///     .await
+    ///     .expect("Failed to read the bootstrap file");
+    /// # };
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`Error::LocalReadError`] if the local file could not be read,
+    /// or an [`Error`] if its content could not be parsed.
+    pub async fn with_bootstrap_from_path(self, local_path: impl AsRef<Path>) -> Result<Self> {
+        if self.as_content().is_some_and(Content::is_remote) {
+            return Ok(self);
+        }
+        let bytes = Bytes::from(std::fs::read(local_path)?);
+        let context = ParseContext::new(self.bucket(), self.path());
+        let body = self
+            .parser()
+            .parse_async(Body::from(bytes), &context)
+            .await
+            .map_err(|e| Error::ParseError(e))?;
+        Ok(self.with_content(String::new(), body, None))
+    }
+
+    /// Compares the content of new versions with the loaded one, ignoring versions with identical content
+    ///
+    /// Useful when objects are uploaded again with the same content, such as by CI pipelines,
+    /// or when multipart uploads change the `ETag` without changing the content.
+    /// The content is hashed with SHA-256, and `fetch` returns `None` when it matches the loaded content,
+    /// without parsing it again. The new `ETag` is still used on the next requests, so they are not downloaded again.
+    ///
+    /// `fetch` buffers the whole content to hash it before parsing, so [`StreamParse`] parsers no longer receive it as it is downloaded.
+    /// `fetch_offloaded` hashes the content as it is fed to the parser instead, skipping only the [`StreamParse::finish`] step for identical content.
+    ///
+    ///  ## Example
+    ///
+    /// ```rust
+    /// use conditional_s3_fetch::File;
+    ///
+    /// let file = File::<String>::unloaded("my-bucket", "/my/path.txt").with_content_hashing();
+    /// ```
+    #[must_use]
+    pub fn with_content_hashing(mut self) -> Self {
+        match &mut self {
+            Self::Unloaded(UnloadedFile { hash_content, .. })
+            | Self::Loaded(LoadedFile { hash_content, .. })
+            | Self::Deleted(DeletedFile { hash_content, .. }) => *hash_content = true,
+        }
+        self
+    }
+
+    fn hashes_content(&self) -> bool {
+        match self {
+            Self::Unloaded(UnloadedFile { hash_content, .. })
+            | Self::Loaded(LoadedFile { hash_content, .. })
+            | Self::Deleted(DeletedFile { hash_content, .. }) => *hash_content,
+        }
+    }
+
+    /// Sets what to do when the object is deleted, instead of keeping the loaded content
+    ///
+    /// With [`DeletionPolicy::MarkDeleted`] or [`DeletionPolicy::Fallback`], `fetch` returns a [`File::Deleted`]
+    /// when the object does not exist, instead of [`Error::NotFound`].
+    /// The deleted file then sends unconditional requests, and loads the object again once it is uploaded.
+    ///
+    ///  ## Example
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use conditional_s3_fetch::{DeletionPolicy, File};
+    ///
+    /// let file = File::<String>::unloaded("my-bucket", "/my/path.txt")
+    ///     .with_deletion_policy(DeletionPolicy::Fallback(Arc::new(String::from("default"))));
+    /// ```
+    #[must_use]
+    pub fn with_deletion_policy(mut self, policy: DeletionPolicy<P::Output>) -> Self {
+        match &mut self {
+            Self::Unloaded(UnloadedFile { deletion, .. })
+            | Self::Loaded(LoadedFile { deletion, .. })
+            | Self::Deleted(DeletedFile { deletion, .. }) => *deletion = policy,
+        }
+        self
+    }
+
+    fn deletion_policy(&self) -> &DeletionPolicy<P::Output> {
+        match self {
+            Self::Unloaded(UnloadedFile { deletion, .. })
+            | Self::Loaded(LoadedFile { deletion, .. })
+            | Self::Deleted(DeletedFile { deletion, .. }) => deletion,
+        }
+    }
+
     /// Creates a reference to a loaded file on S3, already with parsed data
     ///
     /// Useful for initialization process where failure should halt the service.
@@ -368,50 +1185,220 @@ where
         bucket: S,
         path: S,
         s3_client: &aws_sdk_s3::Client,
-    ) -> Result<Self> {
+    ) -> Result<Self>
+    where
+        P: Default,
+    {
         let file = Self::unloaded(bucket, path);
         let fetch = file.fetch(s3_client).await?;
         fetch.ok_or_else(|| Error::UnabledToLoad)
     }
 
-    async fn attempt_extract(&self, response: GetObjectOutput) -> self::Result<Self> {
-        let bytes = response
-            .body
-            .collect()
-            .await
-            .map_err(Error::ReadError)?
-            .into_bytes();
-        let body = P::parse(bytes).map_err(|e| Error::ParseError(e))?;
+    fn shared_parser(&self) -> Arc<P> {
+        match self {
+            Self::Unloaded(UnloadedFile { parser, .. })
+            | Self::Loaded(LoadedFile { parser, .. })
+            | Self::Deleted(DeletedFile { parser, .. }) => Arc::clone(parser),
+        }
+    }
 
-        let etag = response.e_tag.unwrap_or_default();
-        Ok(Self::Loaded(LoadedFile {
+    fn with_content(&self, etag: String, body: P::Output, hash: Option<ContentHash>) -> Self {
+        Self::Loaded(LoadedFile {
             bucket: self.bucket().into(),
             path: self.path().into(),
-            inner: Content { etag, body },
-            parser: PhantomData,
-        }))
+            inner: Content {
+                etag,
+                body: Arc::new(body),
+            },
+            parser: self.shared_parser(),
+            latest: EtagCell::default(),
+            rejected: EtagCell::default(),
+            hash_content: self.hashes_content(),
+            hash,
+            deletion: self.deletion_policy().clone(),
+        })
     }
 
-    /// Attempt to fetch the file from S3 using `If-None-Match` header
+    /// Apply the deletion policy when the object does not exist
     ///
-    /// If the file has not been modified, it returns `None`.
-    /// If the file has been modified, returns a new [`File`] with the new content already parsed.
-    /// If there are any errors during the process, returns an error of [`Error`].
+    /// Returns `None` if the file is already deleted, or the `NotFound` error when keeping the loaded content.
+    fn deleted(&self, not_found: Error) -> self::Result<Option<Self>> {
+        if self.is_deleted() {
+            return Ok(None);
+        }
+        let fallback = match self.deletion_policy() {
+            DeletionPolicy::KeepContent => return Err(not_found),
+            DeletionPolicy::MarkDeleted => None,
+            DeletionPolicy::Fallback(body) => Some(Content {
+                etag: String::new(),
+                body: Arc::clone(body),
+            }),
+        };
+
+        tracing::info!(
+            bucket = self.bucket(),
+            key = self.path(),
+            "Object of the file was deleted"
+        );
+        Ok(Some(Self::Deleted(DeletedFile {
+            bucket: self.bucket().into(),
+            path: self.path().into(),
+            fallback,
+            parser: self.shared_parser(),
+            hash_content: self.hashes_content(),
+            deletion: self.deletion_policy().clone(),
+        })))
+    }
+
+    /// Check whether the new version has the same content as the loaded one, remembering its `ETag` if so
+    fn is_identical(&self, context: &ParseContext, hash: ContentHash) -> bool {
+        let Self::Loaded(LoadedFile {
+            latest,
+            rejected,
+            hash: Some(current),
+            ..
+        }) = self
+        else {
+            return false;
+        };
+        if *current != hash {
+            return false;
+        }
+
+        let etag = context.etag().unwrap_or_default().to_string();
+        tracing::debug!(
+            bucket = self.bucket(),
+            key = self.path(),
+            etag,
+            "New version of the file has identical content"
+        );
+        latest.set(Some(etag));
+        rejected.set(None);
+        true
+    }
+
+    /// Convert an error returned by the parser, remembering versions which are invalid or were rejected
     ///
-    /// # Errors
-    /// Returns an [`Error`] if the content could not be fetched or parsed.
-    #[tracing::instrument(skip_all)]
-    pub async fn fetch(&self, s3_client: &aws_sdk_s3::Client) -> self::Result<Option<self::File<P>>>
-    where
-        P: Parse,
-    {
+    /// Only errors caused by the content itself are remembered, see [`is_invalid_content`].
+    /// Other errors, such as read errors or lookups failing while parsing, may succeed on the next attempt with the same version.
+    fn parse_failure(
+        &self,
+        context: &ParseContext,
+        error: Box<dyn std::error::Error + Send + Sync>,
+    ) -> Error {
+        let error = match error.downcast::<aws_sdk_s3::primitives::ByteStreamError>() {
+            Ok(error) => return Error::ReadError(*error),
+            Err(error) => error,
+        };
+
+        let etag = context.etag().unwrap_or_default().to_string();
+        let (error, remember) = match error.downcast::<Rejection>() {
+            Ok(rejection) => {
+                tracing::warn!(
+                    bucket = self.bucket(),
+                    key = self.path(),
+                    etag,
+                    reason = %rejection,
+                    "Rejected new version of the file"
+                );
+                let error = Error::Rejected {
+                    etag: etag.clone(),
+                    rejection: *rejection,
+                };
+                (error, true)
+            }
+            Err(error) => {
+                let remember = is_invalid_content(&*error);
+                tracing::warn!(
+                    bucket = self.bucket(),
+                    key = self.path(),
+                    etag,
+                    reason = %error,
+                    retry = !remember,
+                    "Failed to parse new version of the file"
+                );
+                (Error::ParseError(error), remember)
+            }
+        };
+        if let Self::Loaded(LoadedFile {
+            latest, rejected, ..
+        }) = self
+        {
+            if remember {
+                latest.set(Some(etag.clone()));
+                rejected.set(Some(etag));
+            }
+        }
+        error
+    }
+
+    /// Parse the new version, or return `None` if its content is identical to the loaded one
+    async fn attempt_extract(&self, response: GetObjectOutput) -> self::Result<Option<Self>> {
+        let context = ParseContext::from_response(self.bucket(), self.path(), &response);
+        let (body, hash) = if self.hashes_content() {
+            let bytes = response.body.collect().await?.into_bytes();
+            let hash = content_hash(&bytes);
+            if self.is_identical(&context, hash) {
+                return Ok(None);
+            }
+            (Body::from(bytes), Some(hash))
+        } else {
+            (Body::from(response.body), None)
+        };
+
+        let body = self
+            .parser()
+            .parse_async(body, &context)
+            .await
+            .map_err(|e| self.parse_failure(&context, e))?;
+
+        Ok(Some(self.with_content(
+            context.etag().unwrap_or_default().into(),
+            body,
+            hash,
+        )))
+    }
+
+    /// Replace the file with the new version, if any
+    fn replace(&mut self, new: Option<Self>) -> Refresh<P::Output> {
+        let Some(new) = new else {
+            return Refresh::Unchanged;
+        };
+        let deleted = new.is_deleted();
+        match std::mem::replace(self, new) {
+            Self::Loaded(LoadedFile { inner, .. }) if deleted => Refresh::Deleted {
+                previous: Some(inner),
+            },
+            Self::Unloaded(_) if deleted => Refresh::Deleted { previous: None },
+            Self::Unloaded(_) | Self::Deleted(_) => Refresh::FirstLoad,
+            Self::Loaded(LoadedFile { inner, .. }) if !inner.is_remote() => Refresh::FirstLoad,
+            Self::Loaded(LoadedFile { inner, .. }) => Refresh::Updated {
+                previous_etag: inner.etag.clone(),
+                previous: inner,
+            },
+        }
+    }
+
+    /// Send the `GetObject` request, using the `If-None-Match` header when the file is loaded
+    ///
+    /// The `ETag` of the latest version which was not loaded is used instead of the loaded one, if any,
+    /// such as a rejected version or a version with identical content.
+    /// Returns `None` if the file has not been modified.
+    /// Deleted files and content not fetched from S3 send unconditional requests, so a new upload is loaded even with the same `ETag`.
+    async fn request(
+        &self,
+        s3_client: &aws_sdk_s3::Client,
+    ) -> self::Result<Option<GetObjectOutput>> {
         let mut response_builder = s3_client
             .get_object()
             .bucket(self.bucket())
             .key(self.path());
 
-        if let File::Loaded(LoadedFile { inner, .. }) = &self {
-            response_builder = response_builder.if_none_match(&inner.etag);
+        if let File::Loaded(LoadedFile { inner, latest, .. }) = &self {
+            let etag = latest.get().unwrap_or_else(|| inner.etag.clone());
+            if !etag.is_empty() {
+                response_builder = response_builder.if_none_match(etag);
+            }
         }
 
         let response = response_builder.send().await;
@@ -419,11 +1406,241 @@ where
             if e.raw().status().as_u16() == 304 {
                 return Ok(None);
             }
+            if e.err().is_no_such_key() {
+                return Err(Error::NotFound {
+                    bucket: self.bucket().into(),
+                    key: self.path().into(),
+                });
+            }
             response.map_err(|e| Error::SdkError(Box::new(e)))?
         } else {
             response.map_err(|e| Error::SdkError(Box::new(e)))?
         };
 
-        Ok(Some(self.attempt_extract(response).await?))
+        Ok(Some(response))
+    }
+
+    /// Attempt to fetch the file from S3 using `If-None-Match` header
+    ///
+    /// If the file has not been modified, it returns `None`.
+    /// If the file has been modified, returns a new [`File`] with the new content already parsed.
+    /// If there are any errors during the process, returns an error of [`Error`].
+    ///
+    /// Returns [`Error::NotFound`] if the object does not exist, unless set otherwise by [`File::with_deletion_policy`].
+    /// Other errors, such as a missing bucket, are returned as [`Error::SdkError`] and never treated as a deletion.
+    ///
+    /// When the new version fails to parse, [`Error::ParseError`] is returned and the current file is kept,
+    /// or [`Error::Rejected`] when it is rejected by a [`Validated`] parser.
+    /// When the content itself is invalid, such as a rejected version or a deserialization error, its `ETag` is remembered,
+    /// so the failed version is not downloaded again until the object changes. Other errors are retried on the next fetch.
+    ///
+    /// With [`File::with_content_hashing`], new versions with the same content as the loaded one return `None`.
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if the content could not be fetched or parsed.
+    #[tracing::instrument(skip_all)]
+    pub async fn fetch(
+        &self,
+        s3_client: &aws_sdk_s3::Client,
+    ) -> self::Result<Option<self::File<P>>> {
+        let response = match self.request(s3_client).await {
+            Ok(Some(response)) => response,
+            Ok(None) => return Ok(None),
+            Err(error @ Error::NotFound { .. }) => return self.deleted(error),
+            Err(error) => return Err(error),
+        };
+
+        self.attempt_extract(response).await
+    }
+
+    /// Fetch the file from S3 using `If-None-Match` header, updating it in place
+    ///
+    /// Behaves like [`File::fetch`], returning how the file changed instead of a new [`File`].
+    /// The file is kept as is when an error is returned.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # fn client() -> aws_sdk_s3::Client { unimplemented!() }
+    /// # async {
+    /// # let s3_client = client();
+    /// use conditional_s3_fetch::{File, Refresh};
+    ///
+    /// let mut file = File::<String>::unloaded("my-bucket", "/my/path.txt");
+    ///
+    /// match file.refresh(&s3_client).await {
+    ///     Ok(Refresh::Unchanged) => println!("No modification"),
+    ///     Ok(Refresh::FirstLoad) => println!("Loaded"),
+    ///     Ok(Refresh::Updated { previous_etag, .. }) => println!("Replaced {previous_etag}"),
+    ///     Ok(Refresh::Deleted { .. }) => println!("Deleted"),
+    ///     Err(e) => eprintln!("Error: {}", e),
+    /// }
+    /// # };
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if the content could not be fetched or parsed.
+    pub async fn refresh(
+        &mut self,
+        s3_client: &aws_sdk_s3::Client,
+    ) -> self::Result<Refresh<P::Output>> {
+        let new = self.fetch(s3_client).await?;
+        Ok(self.replace(new))
+    }
+}
+
+impl<P> File<P>
+where
+    P: StreamParse + Send + Sync + 'static,
+    P::State: Send + 'static,
+    P::Output: Send + 'static,
+{
+    /// Feed the response body to the parser as it is downloaded
+    ///
+    /// Returns the object context, the parser state, the amount of bytes read, and the content hash if enabled.
+    async fn read_body(
+        &self,
+        mut response: GetObjectOutput,
+    ) -> self::Result<(ParseContext, P::State, usize, Option<ContentHash>)> {
+        let context = ParseContext::from_response(self.bucket(), self.path(), &response);
+        let parser = self.parser();
+        let mut state = parser.start(&context);
+        let mut len = 0;
+        let mut hasher = self.hashes_content().then(Sha256::new);
+        while let Some(chunk) = response.body.try_next().await.map_err(Error::ReadError)? {
+            len += chunk.len();
+            if let Some(hasher) = &mut hasher {
+                hasher.update(&chunk);
+            }
+            parser
+                .feed(&mut state, chunk)
+                .map_err(|e| self.parse_failure(&context, e))?;
+        }
+        Ok((context, state, len, hasher.map(|h| h.finalize().into())))
+    }
+
+    /// Attempt to fetch the file from S3 using `If-None-Match` header, parsing large files on an [`Executor`]
+    ///
+    /// Behaves like [`File::fetch`], but files with at least [`Offload::threshold`] bytes are parsed on the [`Offload`] executor
+    /// while the returned future waits, keeping CPU-heavy parsing away from the async executor.
+    ///
+    /// For [`StreamParse`] implementations, only the [`StreamParse::finish`] step is offloaded.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # fn client() -> aws_sdk_s3::Client { unimplemented!() }
+    /// # #[derive(serde::Deserialize)]
+    /// # struct MyStruct;
+    /// # async {
+    /// # let s3_client = client();
+    /// use conditional_s3_fetch::{File, Json, Offload, SpawnBlocking};
+    ///
+    /// let offload = Offload::new(1024 * 1024, SpawnBlocking);
+    /// let mut file = File::<Json<MyStruct>>::unloaded("my-bucket", "/my/large.json");
+    ///
+    /// match file.fetch_offloaded(&s3_client, &offload).await {
+    ///     Ok(Some(new)) => file = new,
+    ///     Ok(None) => println!("No modification"),
+    ///     Err(e) => eprintln!("Error: {}", e),
+    /// }
+    /// # };
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if the content could not be fetched or parsed,
+    /// or [`Error::ParseAborted`] if the executor dropped the parsing task.
+    #[tracing::instrument(skip_all)]
+    pub async fn fetch_offloaded(
+        &self,
+        s3_client: &aws_sdk_s3::Client,
+        offload: &Offload,
+    ) -> self::Result<Option<self::File<P>>> {
+        let response = match self.request(s3_client).await {
+            Ok(Some(response)) => response,
+            Ok(None) => return Ok(None),
+            Err(error @ Error::NotFound { .. }) => return self.deleted(error),
+            Err(error) => return Err(error),
+        };
+
+        let (context, state, len, hash) = self.read_body(response).await?;
+        if hash.is_some_and(|hash| self.is_identical(&context, hash)) {
+            return Ok(None);
+        }
+
+        let parser = self.shared_parser();
+        let (context, body) = offload
+            .run(len, move || {
+                let body = parser.finish(state, &context);
+                (context, body)
+            })
+            .await?;
+        let body = body.map_err(|e| self.parse_failure(&context, e))?;
+
+        Ok(Some(self.with_content(
+            context.etag().unwrap_or_default().into(),
+            body,
+            hash,
+        )))
     }
+
+    /// Fetch the file from S3 using `If-None-Match` header, updating it in place, parsing large files on an [`Executor`]
+    ///
+    /// Behaves like [`File::fetch_offloaded`], returning how the file changed instead of a new [`File`].
+    /// The file is kept as is when an error is returned.
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if the content could not be fetched or parsed,
+    /// or [`Error::ParseAborted`] if the executor dropped the parsing task.
+    pub async fn refresh_offloaded(
+        &mut self,
+        s3_client: &aws_sdk_s3::Client,
+        offload: &Offload,
+    ) -> self::Result<Refresh<P::Output>> {
+        let new = self.fetch_offloaded(s3_client, offload).await?;
+        Ok(self.replace(new))
+    }
+}
+
+/// Check whether a parse error is caused by the content itself, so parsing the same version again would fail too
+///
+/// Covers the decoding errors of the built-in parsers, such as [`DeserializeError`], and a [`FallbackError`](combinators::FallbackError)
+/// when both parsers failed on the content. Other errors are assumed to be transient.
+fn is_invalid_content(error: &(dyn std::error::Error + 'static)) -> bool {
+    if let Some(error) = error.downcast_ref::<combinators::FallbackError>() {
+        return is_invalid_content(error.primary()) && is_invalid_content(error.fallback());
+    }
+    #[cfg(feature = "serde")]
+    if error.is::<DeserializeError>() || error.is::<VersionError>() {
+        return true;
+    }
+    #[cfg(any(feature = "dotenv", feature = "ini", feature = "properties"))]
+    if error.is::<key_value::Error>() {
+        return true;
+    }
+    #[cfg(feature = "json-schema")]
+    if error.is::<json_schema::SchemaViolations>() {
+        return true;
+    }
+    #[cfg(feature = "bincode")]
+    if error.is::<::bincode::error::DecodeError>() {
+        return true;
+    }
+    #[cfg(feature = "postcard")]
+    if error.is::<::postcard::Error>() {
+        return true;
+    }
+    #[cfg(feature = "rkyv")]
+    if error.is::<::rkyv::rancor::Error>() {
+        return true;
+    }
+    error.is::<std::string::FromUtf8Error>()
+}
+
+/// SHA-256 hash of the content of a version, to detect identical content
+type ContentHash = [u8; 32];
+
+/// Hash of the content of a version, equal to hashing the same content chunk by chunk, as done while streaming it
+fn content_hash(content: &[u8]) -> ContentHash {
+    Sha256::digest(content).into()
 }
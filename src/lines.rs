@@ -0,0 +1,100 @@
+//! Line oriented streaming parsers
+//!
+//! Parser implementations which read the content line by line while it is downloaded,
+//! so only the current line is buffered instead of the whole object.
+//!
+//! Lines are split on `\n`, with a trailing `\r` removed.
+//!
+//! # Example
+//!
+//! ```rust
+//! use conditional_s3_fetch::{File, Lines};
+//!
+//! let file = File::<Lines>::unloaded("bucket", "/data/feed.txt");
+//! ```
+use bytes::{Bytes, BytesMut};
+
+/// Parser implementation to read the content as a list of UTF-8 lines
+///
+/// # Example
+///
+///  ```rust
+/// use conditional_s3_fetch::{File, Lines};
+///
+/// let file = File::<Lines>::unloaded("bucket", "/data/feed.txt");
+/// ```
+#[derive(Debug, Default)]
+pub struct Lines;
+
+impl crate::StreamParse for Lines {
+    type Output = Vec<String>;
+    type State = (LineBuffer, Vec<String>);
+
+    fn start(&self, _context: &crate::ParseContext) -> Self::State {
+        Default::default()
+    }
+
+    fn feed(&self, (buffer, lines): &mut Self::State, chunk: Bytes) -> crate::BoxedResult<()> {
+        buffer.push(&chunk, |line| {
+            lines.push(String::from_utf8(line.to_vec())?);
+            Ok(())
+        })
+    }
+
+    fn finish(
+        &self,
+        (buffer, mut lines): Self::State,
+        _context: &crate::ParseContext,
+    ) -> crate::BoxedResult<Self::Output> {
+        buffer.finish(|line| {
+            lines.push(String::from_utf8(line.to_vec())?);
+            Ok(())
+        })?;
+        Ok(lines)
+    }
+}
+
+/// Buffer for a line split across chunks
+#[derive(Debug, Default)]
+pub struct LineBuffer {
+    partial: BytesMut,
+}
+
+impl LineBuffer {
+    /// Calls `f` for every complete line in the chunk, keeping any incomplete line for the next chunk
+    pub(crate) fn push<F>(&mut self, chunk: &[u8], mut f: F) -> crate::BoxedResult<()>
+    where
+        F: FnMut(&[u8]) -> crate::BoxedResult<()>,
+    {
+        let mut rest = chunk;
+        while let Some(end) = rest.iter().position(|b| *b == b'\n') {
+            let line = &rest[..end];
+            rest = &rest[end + 1..];
+
+            if self.partial.is_empty() {
+                f(trim_carriage_return(line))?;
+            } else {
+                self.partial.extend_from_slice(line);
+                let line = self.partial.split();
+                f(trim_carriage_return(&line))?;
+            }
+        }
+        self.partial.extend_from_slice(rest);
+        Ok(())
+    }
+
+    /// Calls `f` for the last line, if the content did not end with a new line
+    pub(crate) fn finish<F>(self, mut f: F) -> crate::BoxedResult<()>
+    where
+        F: FnMut(&[u8]) -> crate::BoxedResult<()>,
+    {
+        if self.partial.is_empty() {
+            return Ok(());
+        }
+        f(trim_carriage_return(&self.partial))
+    }
+}
+
+fn trim_carriage_return(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
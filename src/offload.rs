@@ -0,0 +1,144 @@
+//! Offloading CPU-heavy parsing away from the async executor
+//!
+//! Parsing large files can take a long time, stalling the executor thread polling the fetch future,
+//! and with it any other task scheduled on the same thread.
+//!
+//! [`File::fetch_offloaded`](crate::File::fetch_offloaded) runs the parsing step on an [`Executor`] when the content is large enough,
+//! while the fetch future waits for the result. Smaller files are still parsed inline, avoiding the scheduling overhead.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # fn client() -> aws_sdk_s3::Client { unimplemented!() }
+//! # #[derive(serde::Deserialize)]
+//! # struct MyStruct;
+//! # async {
+//! # let s3_client = client();
+//! use conditional_s3_fetch::{File, Json, Offload, SpawnBlocking};
+//!
+//! // Files over 1MB are parsed on tokio's blocking thread pool
+//! let offload = Offload::new(1024 * 1024, SpawnBlocking);
+//!
+//! let file = File::<Json<MyStruct>>::unloaded("my-bucket", "/my/large.json");
+//! let new_file = file.fetch_offloaded(&s3_client, &offload).await;
+//! # };
+//! ```
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+/// Boxed task sent to an [`Executor`]
+pub type Task = Box<dyn FnOnce() + Send + 'static>;
+
+/// Future resolved once an [`Executor`] has finished running a [`Task`]
+pub type Completion = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Trait to run parsing tasks away from the async executor
+///
+/// The returned future must resolve once the task has finished running.
+/// If the task is dropped without running, the fetch fails with [`Error::ParseAborted`](crate::Error::ParseAborted).
+///
+/// # Example
+///
+/// Running tasks on a dedicated thread, signalling completion with a channel:
+///
+/// ```rust
+/// use conditional_s3_fetch::{Completion, Executor, Task};
+///
+/// struct NewThread;
+///
+/// impl Executor for NewThread {
+///     fn spawn_blocking(&self, task: Task) -> Completion {
+///         let (sender, receiver) = futures::channel::oneshot::channel();
+///         std::thread::spawn(move || {
+///             task();
+///             let _ = sender.send(());
+///         });
+///         Box::pin(async move {
+///             let _ = receiver.await;
+///         })
+///     }
+/// }
+/// ```
+pub trait Executor: Send + Sync {
+    /// Schedule the task to run, returning a future which resolves once it has finished
+    fn spawn_blocking(&self, task: Task) -> Completion;
+}
+
+/// [`Executor`] implementation using tokio's blocking thread pool (feature: `tokio`)
+///
+/// Tasks are scheduled with [`tokio::task::spawn_blocking`], so it must be used from within a tokio runtime.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpawnBlocking;
+
+#[cfg(feature = "tokio")]
+impl Executor for SpawnBlocking {
+    fn spawn_blocking(&self, task: Task) -> Completion {
+        let handle = tokio::task::spawn_blocking(task);
+        Box::pin(async move {
+            let _ = handle.await;
+        })
+    }
+}
+
+/// Configuration to parse files on an [`Executor`] once they reach a size threshold
+#[derive(Clone)]
+pub struct Offload {
+    threshold: usize,
+    executor: Arc<dyn Executor>,
+}
+
+impl Offload {
+    /// Parse files with at least `threshold` bytes on the given executor
+    ///
+    /// Files smaller than the threshold are parsed inline.
+    pub fn new<E>(threshold: usize, executor: E) -> Self
+    where
+        E: Executor + 'static,
+    {
+        Self {
+            threshold,
+            executor: Arc::new(executor),
+        }
+    }
+
+    /// Returns the size, in bytes, from which files are parsed on the executor
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub(crate) async fn run<F, R>(&self, len: usize, f: F) -> crate::Result<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        if len < self.threshold {
+            return Ok(f());
+        }
+
+        tracing::debug!(len, threshold = self.threshold, "Offloading parsing");
+        let slot = Arc::new(Mutex::new(None));
+        let output = Arc::clone(&slot);
+        self.executor
+            .spawn_blocking(Box::new(move || {
+                let result = f();
+                *output.lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
+            }))
+            .await;
+
+        let result = slot.lock().unwrap_or_else(|e| e.into_inner()).take();
+        result.ok_or(crate::Error::ParseAborted)
+    }
+}
+
+impl fmt::Debug for Offload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Offload")
+            .field("threshold", &self.threshold)
+            .finish_non_exhaustive()
+    }
+}
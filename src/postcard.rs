@@ -0,0 +1,58 @@
+//! Postcard parser implementation (feature: `postcard`)
+//!
+//! Parser implementation to read [postcard](https://docs.rs/postcard) data into a deserialized object.
+//!
+//! Postcard has a fixed wire format, so the only option is the maximum size accepted for the content,
+//! provided as a const generic parameter. Content is unbounded when not provided.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize)]
+//! # struct MyStruct;
+//! use conditional_s3_fetch::{File, Postcard};
+//!
+//! let file = File::<Postcard<MyStruct>>::unloaded("bucket", "/data/index.postcard");
+//! let limited = File::<Postcard<MyStruct, { 16 * 1024 * 1024 }>>::unloaded("bucket", "/data/index.postcard");
+//! ```
+use bytes::Bytes;
+
+/// Parser implementation to read postcard data into a deserialized object.
+///
+/// Content larger than `LIMIT` bytes is rejected before decoding.
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize)]
+/// # struct MyStruct;
+/// use conditional_s3_fetch::{File, Postcard};
+///
+/// let file = File::<Postcard<MyStruct, 4096>>::unloaded("bucket", "/data/index.postcard");
+/// ```
+#[derive(Debug)]
+pub struct Postcard<T, const LIMIT: usize = { usize::MAX }>(std::marker::PhantomData<fn() -> T>);
+
+impl<T, const LIMIT: usize> Default for Postcard<T, LIMIT> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+impl<T, const LIMIT: usize> crate::Parse for Postcard<T, LIMIT>
+where
+    T: serde::de::DeserializeOwned,
+{
+    type Output = T;
+
+    fn parse(bytes: Bytes) -> crate::BoxedResult<Self::Output> {
+        if bytes.len() > LIMIT {
+            return Err(format!(
+                "postcard content has {} bytes, over the limit of {LIMIT} bytes",
+                bytes.len()
+            )
+            .into());
+        }
+        Ok(::postcard::from_bytes(&bytes)?)
+    }
+}
@@ -0,0 +1,172 @@
+//! Java `.properties` parser implementation (feature: `properties`)
+//!
+//! Parser implementation to read Java `.properties` files into a deserialized object.
+//!
+//! The supported syntax follows [`java.util.Properties`](https://docs.oracle.com/javase/8/docs/api/java/util/Properties.html#load-java.io.Reader-):
+//! - Keys and values are separated by `=`, `:` or whitespace
+//! - Lines starting with `#` or `!` are comments
+//! - A line ending with an unescaped `\` continues on the next line
+//! - `\t`, `\n`, `\r`, `\f` and `\uXXXX` escapes are decoded, any other escaped character is kept as is
+//!
+//! Content is read as UTF-8, matching the behavior of `PropertyResourceBundle` since Java 9.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize)]
+//! # struct MyStruct;
+//! use conditional_s3_fetch::{File, Properties};
+//!
+//! let file = File::<Properties<MyStruct>>::unloaded("bucket", "/data/app.properties");
+//! ```
+use bytes::Bytes;
+
+use crate::key_value::{self, Entries, Error, Value};
+
+/// Parser implementation to read Java `.properties` data into a deserialized object.
+///
+/// Use [`OrderedMap`](crate::OrderedMap) as the target to keep the entries in declaration order.
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(serde::Deserialize)]
+/// # struct MyStruct;
+/// use conditional_s3_fetch::{File, Properties};
+///
+/// let file = File::<Properties<MyStruct>>::unloaded("bucket", "/data/app.properties");
+/// ```
+#[derive(Debug)]
+pub struct Properties<T>(std::marker::PhantomData<fn() -> T>);
+
+impl<T> Default for Properties<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+impl<T> crate::Parse for Properties<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    type Output = T;
+
+    fn parse(bytes: Bytes) -> crate::BoxedResult<Self::Output> {
+        <Self as crate::SerdeFormat>::from_slice(&bytes)
+    }
+}
+
+impl<T> crate::SerdeFormat for Properties<T> {
+    fn from_slice<U>(bytes: &[u8]) -> crate::BoxedResult<U>
+    where
+        U: serde::de::DeserializeOwned,
+    {
+        let text = std::str::from_utf8(bytes)?;
+        let entries = read(text)?
+            .into_iter()
+            .map(|(key, value)| (key, Value::Text(value)))
+            .collect();
+        Ok(key_value::from_entries(entries)?)
+    }
+}
+
+fn read(text: &str) -> Result<Vec<(String, String)>, Error> {
+    let mut entries = Entries::default();
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line));
+
+    while let Some((number, line)) = lines.next() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+
+        let mut logical = line.to_string();
+        while ends_with_continuation(&logical) {
+            logical.pop();
+            match lines.next() {
+                Some((_, next)) => logical.push_str(next.trim_start()),
+                None => break,
+            }
+        }
+
+        let (key, value) = split_entry(&logical);
+        entries.insert(unescape(key, number)?, unescape(value, number)?);
+    }
+
+    Ok(entries.into_vec())
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+fn split_entry(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '=' | ':' => return (&line[..index], line[index + 1..].trim_start()),
+            c if c.is_whitespace() => {
+                let rest = line[index..].trim_start();
+                let rest = rest.strip_prefix(['=', ':']).map_or(rest, str::trim_start);
+                return (&line[..index], rest);
+            }
+            _ => {}
+        }
+    }
+    (line, "")
+}
+
+fn unescape(text: &str, number: usize) -> Result<String, Error> {
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => value.push('\t'),
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('f') => value.push('\u{000C}'),
+            Some('u') => value.push(unescape_unicode(&mut chars, number)?),
+            Some(other) => value.push(other),
+            None => {}
+        }
+    }
+
+    Ok(value)
+}
+
+/// Decode a `\uXXXX` escape, combining a high surrogate with the low surrogate escape following it
+fn unescape_unicode(chars: &mut std::str::Chars<'_>, number: usize) -> Result<char, Error> {
+    let high = code_unit(chars, number)?;
+    if !(0xD800..0xDC00).contains(&high) {
+        return char::from_u32(high)
+            .ok_or_else(|| Error::syntax(number, format!("invalid escape \\u{high:04X}")));
+    }
+
+    let unpaired = || Error::syntax(number, format!("unpaired surrogate \\u{high:04X}"));
+    if chars.next() != Some('\\') || chars.next() != Some('u') {
+        return Err(unpaired());
+    }
+    let low = code_unit(chars, number)?;
+    if !(0xDC00..0xE000).contains(&low) {
+        return Err(unpaired());
+    }
+    char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).ok_or_else(unpaired)
+}
+
+fn code_unit(chars: &mut std::str::Chars<'_>, number: usize) -> Result<u32, Error> {
+    let code: String = chars.take(4).collect();
+    u32::from_str_radix(&code, 16)
+        .ok()
+        .filter(|_| code.len() == 4)
+        .ok_or_else(|| Error::syntax(number, format!("invalid escape \\u{code}")))
+}
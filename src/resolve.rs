@@ -0,0 +1,291 @@
+//! Cross-object references, inlined before deserializing. (feature `resolve`)
+//!
+//! Documents can reference shared fragments stored in other objects, with `{"$ref": "s3://bucket/key"}`.
+//! [`Resolved`] fetches the referenced objects, replaces each reference with the referenced document,
+//! and deserializes the result into the output of the parser.
+//!
+//! # References
+//!
+//! - `{"$ref": "s3://cfg/common/timeouts.json"}` is replaced by the whole referenced document.
+//! - `{"$ref": "s3://cfg/common/timeouts.json#/default"}` is replaced by the value at the [Json pointer](https://www.rfc-editor.org/rfc/rfc6901) after `#`.
+//! - Referenced documents may contain references too. Cycles are rejected with [`ReferenceError::Cycle`].
+//! - Other members of the reference object are ignored, and `$ref` values not starting with `s3://`
+//!   are kept as is, such as Json Schema local references.
+//!
+//! Only formats keeping the type of the values are supported, Json and Cbor, see [`TypedFormat`].
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize)]
+//! # struct Routes;
+//! use conditional_s3_fetch::{Json, Resolved};
+//!
+//! let routes = Resolved::<Json<Routes>>::new("cfg", "routing/routes.json");
+//! ```
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use serde_json::Value;
+
+use crate::{deserialize::Untyped, DeserializeError, Error, File, Parse, TypedFormat};
+
+/// Document with its `s3://` references inlined, refetching every object which changed
+///
+/// Each fetch sends a conditional request for the root document and every object it references, directly or not.
+/// The references are inlined again when any of them changes, or when the set of referenced objects changes.
+/// A missing referenced object fails the fetch with [`Error::NotFound`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # fn client() -> aws_sdk_s3::Client { unimplemented!() }
+/// # #[derive(serde::Deserialize)]
+/// # struct Routes;
+/// # async {
+/// # let s3_client = client();
+/// use conditional_s3_fetch::{Json, Resolved};
+///
+/// let mut routes = Resolved::<Json<Routes>>::new("cfg", "routing/routes.json");
+///
+/// match routes.fetch(&s3_client).await {
+///     Ok(Some(new)) => routes = new,
+///     Ok(None) => println!("No modification"),
+///     Err(e) => eprintln!("Error: {}", e),
+/// }
+/// # };
+/// ```
+pub struct Resolved<P>
+where
+    P: Parse + TypedFormat,
+{
+    root: String,
+    files: HashMap<String, Arc<File<Untyped<P>>>>,
+    resolved: Option<P::Output>,
+}
+
+impl<P> Resolved<P>
+where
+    P: Parse + TypedFormat + 'static,
+    P::Output: serde::de::DeserializeOwned,
+{
+    /// Creates a reference to an unresolved document on S3
+    pub fn new<S: Into<String>>(bucket: S, path: S) -> Self {
+        Self {
+            root: format!("s3://{}/{}", bucket.into(), path.into()),
+            files: HashMap::new(),
+            resolved: None,
+        }
+    }
+
+    /// Returns the `s3://` location of the root document
+    pub fn location(&self) -> &str {
+        &self.root
+    }
+
+    /// Returns the `s3://` locations of the objects referenced by the root document, directly or not
+    pub fn references(&self) -> impl Iterator<Item = &str> {
+        self.files
+            .keys()
+            .map(String::as_str)
+            .filter(|location| *location != self.root)
+    }
+
+    /// Return the reference to the resolved value, once fetched
+    pub fn as_content(&self) -> Option<&P::Output> {
+        self.resolved.as_ref()
+    }
+
+    /// Returns the resolved value, once fetched
+    pub fn into_inner(self) -> Option<P::Output> {
+        self.resolved
+    }
+
+    /// Attempt to fetch the root document and every referenced object from S3 using `If-None-Match` header
+    ///
+    /// If no object has been modified, it returns `None`.
+    /// Otherwise, returns a new [`Resolved`] with the references inlined again.
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if an object could not be fetched or parsed, if a reference is invalid or cyclic,
+    /// or if the resolved value could not be deserialized.
+    #[tracing::instrument(skip_all, fields(location = self.root))]
+    pub async fn fetch(&self, s3_client: &aws_sdk_s3::Client) -> crate::Result<Option<Self>> {
+        let mut files = HashMap::new();
+        let mut pending = vec![self.root.clone()];
+        let mut modified = self.resolved.is_none();
+
+        while let Some(location) = pending.pop() {
+            if files.contains_key(&location) {
+                continue;
+            }
+            let file = match self.files.get(&location) {
+                Some(file) => Arc::clone(file),
+                None => {
+                    let (bucket, key) = split_location(&location).map_err(parse_error)?;
+                    Arc::new(File::with_parser(bucket, key, Untyped::default()))
+                }
+            };
+            let file = match file.fetch(s3_client).await? {
+                Some(new) => {
+                    modified = true;
+                    Arc::new(new)
+                }
+                None => file,
+            };
+            if let Some(document) = file.as_content() {
+                collect_references(document, &mut pending).map_err(parse_error)?;
+            }
+            files.insert(location, file);
+        }
+
+        if !modified {
+            return Ok(None);
+        }
+
+        let mut chain = vec![self.root.clone()];
+        let document = files[&self.root].as_content().ok_or(Error::UnabledToLoad)?;
+        let document = inline(document, &files, &mut chain).map_err(parse_error)?;
+        let resolved = serde_path_to_error::deserialize(document)
+            .map_err(|e| parse_error(DeserializeError::new(&e)))?;
+
+        Ok(Some(Self {
+            root: self.root.clone(),
+            files,
+            resolved: Some(resolved),
+        }))
+    }
+}
+
+impl<P> fmt::Debug for Resolved<P>
+where
+    P: Parse + TypedFormat,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resolved")
+            .field("root", &self.root)
+            .field("objects", &self.files.len())
+            .field("loaded", &self.resolved.is_some())
+            .finish()
+    }
+}
+
+fn parse_error<E: std::error::Error + Send + 'static>(error: E) -> Error {
+    Error::ParseError(Box::new(error))
+}
+
+/// Returns the `s3://` reference of a value, if it is a reference object
+fn reference(value: &Value) -> Option<&str> {
+    match value.get("$ref") {
+        Some(Value::String(reference)) if reference.starts_with("s3://") => Some(reference),
+        _ => None,
+    }
+}
+
+/// Split a reference into the object location and the Json pointer, if any
+fn split_reference(reference: &str) -> (&str, Option<&str>) {
+    match reference.split_once('#') {
+        Some((location, pointer)) => (location, Some(pointer)),
+        None => (reference, None),
+    }
+}
+
+/// Split an object location into its bucket and key
+fn split_location(location: &str) -> Result<(&str, &str), ReferenceError> {
+    location
+        .strip_prefix("s3://")
+        .and_then(|path| path.split_once('/'))
+        .filter(|(bucket, key)| !bucket.is_empty() && !key.is_empty())
+        .ok_or_else(|| ReferenceError::Invalid {
+            reference: location.to_string(),
+        })
+}
+
+/// Queue the locations of every object referenced by `document`
+fn collect_references(document: &Value, pending: &mut Vec<String>) -> Result<(), ReferenceError> {
+    if let Some(reference) = reference(document) {
+        let (location, _) = split_reference(reference);
+        split_location(location)?;
+        pending.push(location.to_string());
+        return Ok(());
+    }
+    match document {
+        Value::Array(values) => values
+            .iter()
+            .try_for_each(|value| collect_references(value, pending)),
+        Value::Object(members) => members
+            .values()
+            .try_for_each(|value| collect_references(value, pending)),
+        _ => Ok(()),
+    }
+}
+
+/// Replace every reference of `document` with the referenced value, `chain` being the references being inlined
+fn inline<P>(
+    document: &Value,
+    files: &HashMap<String, Arc<File<Untyped<P>>>>,
+    chain: &mut Vec<String>,
+) -> Result<Value, ReferenceError>
+where
+    P: TypedFormat,
+{
+    if let Some(reference) = reference(document) {
+        if chain.iter().any(|previous| previous == reference) {
+            let mut chain = chain.clone();
+            chain.push(reference.to_string());
+            return Err(ReferenceError::Cycle { chain });
+        }
+
+        let (location, pointer) = split_reference(reference);
+        let referenced = files
+            .get(location)
+            .and_then(|file| file.as_content())
+            .map(|content| &**content);
+        let referenced = match pointer {
+            Some(pointer) => referenced.and_then(|value| value.pointer(pointer)),
+            None => referenced,
+        };
+        let referenced = referenced.ok_or_else(|| ReferenceError::Missing {
+            reference: reference.to_string(),
+        })?;
+
+        chain.push(reference.to_string());
+        let value = inline(referenced, files, chain)?;
+        chain.pop();
+        return Ok(value);
+    }
+
+    match document {
+        Value::Array(values) => values
+            .iter()
+            .map(|value| inline(value, files, chain))
+            .collect(),
+        Value::Object(members) => members
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), inline(value, files, chain)?)))
+            .collect(),
+        value => Ok(value.clone()),
+    }
+}
+
+/// Error returned by [`Resolved`] when a reference could not be inlined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceError {
+    /// The reference is not a `s3://bucket/key` location
+    Invalid { reference: String },
+    /// The Json pointer of the reference does not match any value in the referenced document
+    Missing { reference: String },
+    /// The reference is already being inlined, listing the references from the root document
+    Cycle { chain: Vec<String> },
+}
+
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid { reference } => write!(f, "Invalid reference {reference}"),
+            Self::Missing { reference } => write!(f, "Missing value for reference {reference}"),
+            Self::Cycle { chain } => write!(f, "Cyclic reference: {}", chain.join(" -> ")),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceError {}
@@ -0,0 +1,128 @@
+//! Zero-copy rkyv parser implementation (feature: `rkyv`)
+//!
+//! Parser implementation to validate [rkyv](https://docs.rs/rkyv) archives and access them without deserializing.
+//!
+//! Large datasets can be used straight from the downloaded buffer, avoiding the time and memory spent on deserialization.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(rkyv::Archive)]
+//! # struct MyStruct;
+//! use conditional_s3_fetch::{File, Rkyv};
+//!
+//! let file = File::<Rkyv<MyStruct>>::unloaded("bucket", "/data/index.rkyv");
+//! ```
+use std::{fmt, marker::PhantomData, ops::Deref};
+
+use ::rkyv::{api::high::HighValidator, bytecheck::CheckBytes, rancor, util::AlignedVec, Archive};
+use bytes::Bytes;
+
+/// Alignment of the buffer used when the fetched content is not properly aligned
+const ALIGNMENT: usize = 16;
+
+/// Parser implementation to validate rkyv data and expose the archived value.
+///
+/// The content is validated once while parsing and the resulting [`ArchivedContent`] dereferences to the archived `T`.
+///
+/// # Example
+///
+///  ```rust
+/// # #[derive(rkyv::Archive)]
+/// # struct MyStruct;
+/// use conditional_s3_fetch::{File, Rkyv};
+///
+/// let file = File::<Rkyv<MyStruct>>::unloaded("bucket", "/data/index.rkyv");
+/// ```
+#[derive(Debug)]
+pub struct Rkyv<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for Rkyv<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> crate::Parse for Rkyv<T>
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<HighValidator<'a, rancor::Error>>,
+{
+    type Output = ArchivedContent<T>;
+
+    fn parse(bytes: Bytes) -> crate::BoxedResult<Self::Output> {
+        let alignment = std::mem::align_of::<T::Archived>();
+        if alignment > ALIGNMENT {
+            return Err(format!("archived type requires an alignment of {alignment} bytes, over the supported {ALIGNMENT} bytes").into());
+        }
+
+        // Nested archived values may require more than the alignment of the root, such as a `Vec<u64>` in a struct of `u32`,
+        // so the content is only borrowed when it is aligned as rkyv writes its archives.
+        let buffer = if (bytes.as_ptr() as usize).is_multiple_of(ALIGNMENT) {
+            Buffer::Shared(bytes)
+        } else {
+            tracing::debug!("Copying misaligned rkyv content into an aligned buffer");
+            let mut aligned = AlignedVec::<ALIGNMENT>::with_capacity(bytes.len());
+            aligned.extend_from_slice(&bytes);
+            Buffer::Aligned(aligned)
+        };
+
+        ::rkyv::access::<T::Archived, rancor::Error>(buffer.as_slice())?;
+        Ok(ArchivedContent {
+            buffer,
+            archived: PhantomData,
+        })
+    }
+}
+
+enum Buffer {
+    Shared(Bytes),
+    Aligned(AlignedVec<ALIGNMENT>),
+}
+
+impl Buffer {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Self::Shared(bytes) => bytes,
+            Self::Aligned(aligned) => aligned,
+        }
+    }
+}
+
+/// Validated rkyv archive which owns the fetched content
+///
+/// It implements [`Deref`] to the archived `T`, so the archived fields can be used directly.
+/// The content is only copied when the fetched buffer is not aligned to 16 bytes, as rkyv writes its archives.
+pub struct ArchivedContent<T> {
+    buffer: Buffer,
+    archived: PhantomData<fn() -> T>,
+}
+
+impl<T> ArchivedContent<T> {
+    /// Returns the raw archive content
+    pub fn as_bytes(&self) -> &[u8] {
+        self.buffer.as_slice()
+    }
+}
+
+impl<T> Deref for ArchivedContent<T>
+where
+    T: Archive,
+{
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the buffer is immutable and was validated as a `T::Archived` when parsed.
+        unsafe { ::rkyv::access_unchecked::<T::Archived>(self.buffer.as_slice()) }
+    }
+}
+
+impl<T> fmt::Debug for ArchivedContent<T>
+where
+    T: Archive,
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.deref().fmt(f)
+    }
+}
@@ -0,0 +1,425 @@
+//! Schema versioned documents, migrated to the current shape
+//!
+//! Documents stored on S3 may have been written with older shapes of the output type.
+//! The [`Versioned`] parser reads the version marker of the document, from user metadata or from a document field,
+//! deserializes the shape of that version, and applies the registered migrations up to the current shape.
+//!
+//! Documents with a version newer than the latest registered one are rejected with a [`VersionError`].
+//!
+//! # Example
+//!
+//! ```rust
+//! # #[derive(serde::Deserialize)]
+//! # struct ConfigV1 { timeout: u32 }
+//! # #[derive(serde::Deserialize)]
+//! # struct ConfigV2 { timeout_ms: u32 }
+//! # #[derive(serde::Deserialize)]
+//! # struct Config { timeout_ms: u32, retries: u32 }
+//! use conditional_s3_fetch::{File, Json, Versioned};
+//!
+//! // Version 1 is the oldest shape, each migration adds a version
+//! let parser = Versioned::<Json<Config>>::starting_at::<ConfigV1>(1)
+//!     .migrate(|v1: ConfigV1| ConfigV2 { timeout_ms: v1.timeout * 1000 })
+//!     .migrate(|v2: ConfigV2| Config { timeout_ms: v2.timeout_ms, retries: 3 })
+//!     .build();
+//!
+//! let file = File::with_parser("my-bucket", "/my/config.json", parser);
+//! ```
+use std::{any::Any, collections::HashMap, fmt, marker::PhantomData};
+
+use bytes::Bytes;
+
+use crate::{BoxedResult, ParseContext, SerdeFormat};
+
+/// Shape of a single version, before being migrated
+type Shape = Box<dyn Any + Send>;
+
+/// Location of the version marker of a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionMarker {
+    /// User metadata entry, without the `x-amz-meta-` prefix, such as `schema-version`
+    Metadata(String),
+    /// Top level field of the document, such as `schema_version`
+    Field(String),
+}
+
+/// Reads the version from the `x-amz-meta-schema-version` header
+impl Default for VersionMarker {
+    fn default() -> Self {
+        Self::Metadata("schema-version".into())
+    }
+}
+
+impl fmt::Display for VersionMarker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Metadata(key) => write!(f, "metadata x-amz-meta-{key}"),
+            Self::Field(name) => write!(f, "field {name}"),
+        }
+    }
+}
+
+/// Deserialization and migration of a single version
+struct Step {
+    deserialize: fn(&[u8]) -> BoxedResult<Shape>,
+    migrate: Option<Box<dyn Fn(Shape) -> Shape + Send + Sync>>,
+}
+
+fn deserialize_shape<P, S>(bytes: &[u8]) -> BoxedResult<Shape>
+where
+    P: SerdeFormat,
+    S: serde::de::DeserializeOwned + Send + 'static,
+{
+    Ok(Box::new(P::from_slice::<S>(bytes)?))
+}
+
+/// Parser implementation to read schema versioned documents, migrating older versions to the output of `P`
+///
+/// `P` is a serde based parser, such as `Json<Config>` or `Cbor<Config>`, which provides the format and the current shape.
+/// Register the shapes and migrations with [`Versioned::starting_at`], and provide the parser with [`File::with_parser`](crate::File::with_parser).
+///
+/// The version is read from the `x-amz-meta-schema-version` header by default. Use [`Versioned::with_marker`] to read it from elsewhere.
+pub struct Versioned<P>
+where
+    P: crate::Parse,
+{
+    first: u64,
+    steps: Vec<Step>,
+    marker: VersionMarker,
+    default_version: Option<u64>,
+    parser: PhantomData<fn() -> P>,
+}
+
+impl<P> Versioned<P>
+where
+    P: crate::Parse + SerdeFormat,
+    P::Output: serde::de::DeserializeOwned + Send + 'static,
+{
+    /// Creates a parser for documents without older shapes, at the given version
+    pub fn new(version: u64) -> Self {
+        Self::starting_at::<P::Output>(version).build()
+    }
+
+    /// Starts registering migrations, from the oldest supported shape `S` at the given version
+    pub fn starting_at<S>(version: u64) -> Migrations<P, S>
+    where
+        S: serde::de::DeserializeOwned + Send + 'static,
+    {
+        Migrations {
+            first: version,
+            steps: vec![Step {
+                deserialize: deserialize_shape::<P, S>,
+                migrate: None,
+            }],
+            parser: PhantomData,
+            shape: PhantomData,
+        }
+    }
+}
+
+impl<P> Versioned<P>
+where
+    P: crate::Parse,
+{
+    /// Sets where the version marker is read from
+    #[must_use]
+    pub fn with_marker(mut self, marker: VersionMarker) -> Self {
+        self.marker = marker;
+        self
+    }
+
+    /// Sets the version of documents without a version marker, instead of rejecting them
+    #[must_use]
+    pub fn with_default_version(mut self, version: u64) -> Self {
+        self.default_version = Some(version);
+        self
+    }
+
+    /// Returns the oldest supported version
+    pub fn oldest_version(&self) -> u64 {
+        self.first
+    }
+
+    /// Returns the latest supported version, of the current shape
+    pub fn latest_version(&self) -> u64 {
+        self.first + self.steps.len() as u64 - 1
+    }
+
+    fn version(&self, bytes: &[u8], context: &ParseContext) -> BoxedResult<u64>
+    where
+        P: SerdeFormat,
+    {
+        let marker = match &self.marker {
+            VersionMarker::Metadata(key) => context
+                .metadata(key)
+                .map(|value| MarkerValue::Text(value.to_string())),
+            VersionMarker::Field(name) => {
+                let mut fields = P::from_slice::<HashMap<String, MarkerValue>>(bytes)?;
+                fields.remove(name)
+            }
+        };
+
+        match marker {
+            Some(MarkerValue::Number(version)) => Ok(version),
+            Some(MarkerValue::Text(text)) => text.trim().parse().map_err(|_| {
+                VersionError::Invalid {
+                    marker: self.marker.clone(),
+                    value: text.to_string(),
+                }
+                .into()
+            }),
+            Some(MarkerValue::Other) => Err(VersionError::Invalid {
+                marker: self.marker.clone(),
+                value: "a non numeric value".into(),
+            }
+            .into()),
+            None => self.default_version.ok_or_else(|| {
+                VersionError::Missing {
+                    marker: self.marker.clone(),
+                }
+                .into()
+            }),
+        }
+    }
+}
+
+impl<P> fmt::Debug for Versioned<P>
+where
+    P: crate::Parse,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Versioned")
+            .field("oldest_version", &self.oldest_version())
+            .field("latest_version", &self.latest_version())
+            .field("marker", &self.marker)
+            .field("default_version", &self.default_version)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P> crate::ParseWithContext for Versioned<P>
+where
+    P: crate::Parse + SerdeFormat,
+    P::Output: 'static,
+{
+    type Output = P::Output;
+
+    fn parse_with_context(
+        &self,
+        bytes: Bytes,
+        context: &ParseContext,
+    ) -> BoxedResult<Self::Output> {
+        let version = self.version(&bytes, context)?;
+        if version > self.latest_version() {
+            return Err(VersionError::TooNew {
+                version,
+                latest: self.latest_version(),
+            }
+            .into());
+        }
+        if version < self.first {
+            return Err(VersionError::TooOld {
+                version,
+                oldest: self.first,
+            }
+            .into());
+        }
+
+        let steps = &self.steps[usize::try_from(version - self.first)?..];
+        let mut shape = (steps[0].deserialize)(&bytes)?;
+        for step in steps {
+            if let Some(migrate) = &step.migrate {
+                shape = migrate(shape);
+            }
+        }
+        match shape.downcast::<P::Output>() {
+            Ok(output) => Ok(*output),
+            Err(_) => unreachable!("The last migration produces the output shape"),
+        }
+    }
+}
+
+/// Builder registering the migrations of a [`Versioned`] parser, with `S` as the latest registered shape
+///
+/// Each migration adds a version, converting the previous shape into the next one.
+/// Once the latest shape is the output of `P`, use [`Migrations::build`] to create the parser.
+pub struct Migrations<P, S> {
+    first: u64,
+    steps: Vec<Step>,
+    parser: PhantomData<fn() -> P>,
+    shape: PhantomData<fn() -> S>,
+}
+
+impl<P, S> Migrations<P, S>
+where
+    P: SerdeFormat,
+    S: Send + 'static,
+{
+    /// Registers the next version, with the migration from the latest registered shape `S`
+    pub fn migrate<N, F>(mut self, migration: F) -> Migrations<P, N>
+    where
+        N: serde::de::DeserializeOwned + Send + 'static,
+        F: Fn(S) -> N + Send + Sync + 'static,
+    {
+        if let Some(last) = self.steps.last_mut() {
+            last.migrate = Some(Box::new(move |shape: Shape| {
+                let Ok(shape) = shape.downcast::<S>() else {
+                    unreachable!("Each step deserializes the shape of its migration");
+                };
+                Box::new(migration(*shape))
+            }));
+        }
+        self.steps.push(Step {
+            deserialize: deserialize_shape::<P, N>,
+            migrate: None,
+        });
+        Migrations {
+            first: self.first,
+            steps: self.steps,
+            parser: PhantomData,
+            shape: PhantomData,
+        }
+    }
+}
+
+impl<P> Migrations<P, P::Output>
+where
+    P: crate::Parse,
+{
+    /// Creates the parser, with the current shape as the latest version
+    pub fn build(self) -> Versioned<P> {
+        Versioned {
+            first: self.first,
+            steps: self.steps,
+            marker: VersionMarker::default(),
+            default_version: None,
+            parser: PhantomData,
+        }
+    }
+}
+
+impl<P, S> fmt::Debug for Migrations<P, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Migrations")
+            .field("first", &self.first)
+            .field("versions", &self.steps.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Error returned by [`Versioned`] when the document version is not supported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionError {
+    /// The document has no version marker
+    Missing { marker: VersionMarker },
+    /// The version marker is not a number
+    Invalid {
+        marker: VersionMarker,
+        value: String,
+    },
+    /// The document was written by a newer release, with a version this parser does not know
+    TooNew { version: u64, latest: u64 },
+    /// The document version is older than the oldest registered shape
+    TooOld { version: u64, oldest: u64 },
+}
+
+impl fmt::Display for VersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { marker } => write!(f, "Missing schema version on {marker}"),
+            Self::Invalid { marker, value } => {
+                write!(f, "Invalid schema version on {marker}: {value}")
+            }
+            Self::TooNew { version, latest } => write!(
+                f,
+                "Schema version {version} is newer than the latest supported version {latest}"
+            ),
+            Self::TooOld { version, oldest } => write!(
+                f,
+                "Schema version {version} is older than the oldest supported version {oldest}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VersionError {}
+
+/// Value of a top level field, keeping only what a version marker may be
+enum MarkerValue {
+    Number(u64),
+    Text(String),
+    Other,
+}
+
+impl<'de> serde::Deserialize<'de> for MarkerValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = MarkerValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("any value")
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(MarkerValue::Number(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(u64::try_from(v).map_or(MarkerValue::Other, MarkerValue::Number))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(MarkerValue::Text(v.to_string()))
+            }
+
+            fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+                Ok(MarkerValue::Other)
+            }
+
+            fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+                Ok(MarkerValue::Other)
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok(MarkerValue::Other)
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E> {
+                Ok(MarkerValue::Other)
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                serde::Deserialize::deserialize(deserializer)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                while seq.next_element::<serde::de::IgnoredAny>()?.is_some() {}
+                Ok(MarkerValue::Other)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                while map
+                    .next_entry::<serde::de::IgnoredAny, serde::de::IgnoredAny>()?
+                    .is_some()
+                {}
+                Ok(MarkerValue::Other)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
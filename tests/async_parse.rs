@@ -0,0 +1,125 @@
+use aws_sdk_s3::{
+    config::{Credentials, Region},
+    primitives::SdkBody,
+    Client, Config,
+};
+use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+use conditional_s3_fetch::{AsyncParse, Body, BoxedResult, File, ParseContext};
+
+fn test_client(replay_client: StaticReplayClient) -> Client {
+    Client::from_conf(
+        Config::builder()
+            .behavior_version_latest()
+            .credentials_provider(Credentials::new(
+                "ATESTCLIENT",
+                "astestsecretkey",
+                Some("atestsessiontoken".to_string()),
+                None,
+                "",
+            ))
+            .region(Region::new("us-east-1"))
+            .http_client(replay_client)
+            .build(),
+    )
+}
+
+/// Decodes the content with a key looked up while parsing
+struct Obfuscated {
+    keys: tokio::sync::RwLock<std::collections::HashMap<String, u8>>,
+}
+
+impl AsyncParse for Obfuscated {
+    type Output = String;
+
+    async fn parse_async(&self, body: Body, context: &ParseContext) -> BoxedResult<String> {
+        let key_id = context.metadata("key-id").ok_or("Missing key id")?;
+        let key = *self.keys.read().await.get(key_id).ok_or("Unknown key")?;
+        let content = body.bytes().await?;
+        Ok(String::from_utf8(
+            content.iter().map(|byte| byte ^ key).collect(),
+        )?)
+    }
+}
+
+#[tokio::test]
+async fn test_async_parsing() {
+    let encoded: Vec<u8> = b"hello".iter().map(|byte| byte ^ 7).collect();
+    let req1 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"123\"")
+            .header("x-amz-meta-key-id", "first")
+            .body(SdkBody::from(encoded))
+            .unwrap(),
+    );
+    let req2 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .header("If-None-Match", "\"123\"")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"456\"")
+            .header("x-amz-meta-key-id", "missing")
+            .body(SdkBody::from("world"))
+            .unwrap(),
+    );
+    let replay_client = StaticReplayClient::new(vec![req1, req2]);
+    let client = test_client(replay_client.clone());
+
+    let parser = Obfuscated {
+        keys: tokio::sync::RwLock::new([("first".to_string(), 7)].into()),
+    };
+    let file = File::with_parser("test-bucket", "test-prefix", parser)
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is modified");
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("hello"));
+
+    let error = file
+        .fetch(&client)
+        .await
+        .expect_err("Key should be unknown");
+    assert_eq!(error.to_string(), "Parse Error: Unknown key");
+}
+
+#[tokio::test]
+async fn test_parse_implementations_are_async() {
+    let context = ParseContext::new("test-bucket", "test-prefix");
+
+    let parsed = String::new()
+        .parse_async(Body::from(bytes::Bytes::from("hello")), &context)
+        .await
+        .unwrap();
+    assert_eq!(parsed, "hello");
+}
+
+#[cfg(any(feature = "json", feature = "simd-json"))]
+#[tokio::test]
+async fn test_parsers_accept_outputs_which_are_not_sync() {
+    use conditional_s3_fetch::Json;
+
+    #[derive(serde::Deserialize)]
+    struct Counter {
+        hits: std::cell::Cell<u32>,
+    }
+
+    let context = ParseContext::new("test-bucket", "test-prefix");
+    let file = File::<Json<Counter>>::unloaded("test-bucket", "test-prefix");
+
+    let parsed = file
+        .parser()
+        .parse_async(Body::from(bytes::Bytes::from(r#"{"hits": 1}"#)), &context)
+        .await
+        .unwrap();
+    assert_eq!(parsed.hits.get(), 1);
+}
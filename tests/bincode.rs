@@ -0,0 +1,95 @@
+#[cfg(feature = "bincode")]
+mod parsing {
+    use std::ops::Deref;
+
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        primitives::SdkBody,
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::bincode::config::{self, BigEndian, Configuration, Fixint, Limit};
+    use conditional_s3_fetch::{Bincode, File, Parse};
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(serde::Deserialize, serde::Serialize, Eq, PartialEq, Debug)]
+    struct MyStruct {
+        key: String,
+        count: u64,
+    }
+
+    fn data() -> MyStruct {
+        MyStruct {
+            key: "value".to_string(),
+            count: 300,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parsing_bincode() {
+        let response = bincode::serde::encode_to_vec(data(), config::standard()).unwrap();
+
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .body(SdkBody::from(response))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::<Bincode<MyStruct>>::loaded("test-bucket", "test-prefix", &client)
+            .await
+            .expect("Failed to fetch file");
+
+        replay_client.assert_requests_match(&[]);
+        assert_eq!(file.as_content().map(|f| f.deref()), Some(&data()));
+    }
+
+    #[test]
+    fn test_parsing_with_configuration() {
+        type Legacy = Configuration<BigEndian, Fixint>;
+        let encoded = bincode::serde::encode_to_vec(data(), Legacy::default()).unwrap();
+
+        assert_eq!(
+            Bincode::<MyStruct, Legacy>::parse(encoded.clone().into()).unwrap(),
+            data()
+        );
+        assert!(Bincode::<MyStruct>::parse(encoded.clone().into()).is_err());
+        assert!(
+            Bincode::<MyStruct, Configuration<BigEndian, Fixint, Limit<8>>>::parse(encoded.into())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parsing_failure() {
+        let mut encoded = bincode::serde::encode_to_vec(data(), config::standard()).unwrap();
+        encoded.push(0);
+
+        assert!(Bincode::<MyStruct>::parse(encoded.into()).is_err());
+    }
+}
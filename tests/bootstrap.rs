@@ -0,0 +1,125 @@
+mod common;
+
+use aws_sdk_s3::{
+    config::{Credentials, Region},
+    primitives::SdkBody,
+    Client, Config,
+};
+use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+use conditional_s3_fetch::{
+    BoxedResult, Error, File, Lines, ParseContext, ParseWithContext, Refresh,
+};
+
+use crate::common::{request, response};
+
+fn test_client(replay_client: StaticReplayClient) -> Client {
+    Client::from_conf(
+        Config::builder()
+            .behavior_version_latest()
+            .credentials_provider(Credentials::new(
+                "ATESTCLIENT",
+                "astestsecretkey",
+                Some("atestsessiontoken".to_string()),
+                None,
+                "",
+            ))
+            .region(Region::new("us-east-1"))
+            .http_client(replay_client)
+            .build(),
+    )
+}
+
+#[tokio::test]
+async fn test_default_replaced_by_first_fetch() {
+    let replay_client = StaticReplayClient::new(vec![
+        ReplayEvent::new(request(None), response("\"123\"", "hello")),
+        ReplayEvent::new(
+            request(Some("\"123\"")),
+            http::Response::builder()
+                .status(304)
+                .body(SdkBody::empty())
+                .unwrap(),
+        ),
+    ]);
+    let client = test_client(replay_client.clone());
+
+    let mut file = File::<String>::with_default("test-bucket", "test-prefix", "default".into());
+    let content = file.as_content().expect("Default content is loaded");
+    assert_eq!(content.as_str(), "default");
+    assert_eq!(content.etag(), "");
+    assert!(!content.is_remote());
+
+    let first = file.refresh(&client).await.expect("Failed to fetch file");
+    assert_eq!(first, Refresh::FirstLoad);
+    let content = file.as_content().expect("File is loaded");
+    assert_eq!(content.as_str(), "hello");
+    assert!(content.is_remote());
+
+    let unchanged = file.refresh(&client).await.expect("Failed to fetch file");
+    assert_eq!(unchanged, Refresh::Unchanged);
+
+    replay_client.assert_requests_match(&[]);
+}
+
+#[tokio::test]
+async fn test_bootstrap_from_path() {
+    let local_path = std::env::temp_dir().join(format!("bootstrap-{}.txt", std::process::id()));
+    std::fs::write(&local_path, "first\nsecond\n").unwrap();
+
+    let file = File::<Lines>::bootstrap_from_path("test-bucket", "test-prefix", &local_path)
+        .await
+        .expect("Failed to read the bootstrap file");
+    std::fs::remove_file(&local_path).unwrap();
+
+    let content = file.as_content().expect("Bootstrap content is loaded");
+    assert!(!content.is_remote());
+    assert_eq!(content.as_slice(), ["first", "second"]);
+}
+
+#[tokio::test]
+async fn test_bootstrap_from_missing_path() {
+    let local_path = std::env::temp_dir().join("bootstrap-missing.txt");
+
+    let file = File::<String>::bootstrap_from_path("test-bucket", "test-prefix", local_path).await;
+
+    assert!(matches!(file, Err(Error::LocalReadError(_))));
+}
+
+/// Parser without a `Default` value, which needs to be provided as an instance
+struct Prefixed(&'static str);
+
+impl ParseWithContext for Prefixed {
+    type Output = String;
+
+    fn parse_with_context(
+        &self,
+        data: bytes::Bytes,
+        _context: &ParseContext,
+    ) -> BoxedResult<String> {
+        Ok(format!("{}{}", self.0, String::from_utf8(data.to_vec())?))
+    }
+}
+
+#[tokio::test]
+async fn test_bootstrap_with_parser_instance() {
+    let file = File::with_parser("test-bucket", "test-prefix", Prefixed("> "))
+        .with_default_content("default".into());
+    let content = file.as_content().expect("Default content is loaded");
+    assert!(!content.is_remote());
+    assert_eq!(content.as_str(), "default");
+
+    let local_path =
+        std::env::temp_dir().join(format!("bootstrap-parser-{}.txt", std::process::id()));
+    std::fs::write(&local_path, "local").unwrap();
+
+    let file = file
+        .with_bootstrap_from_path(&local_path)
+        .await
+        .expect("Failed to read the bootstrap file");
+    std::fs::remove_file(&local_path).unwrap();
+
+    let content = file.as_content().expect("Bootstrap content is loaded");
+    assert!(!content.is_remote());
+    assert_eq!(content.as_str(), "> local");
+}
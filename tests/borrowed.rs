@@ -0,0 +1,104 @@
+#[cfg(feature = "borrowed")]
+mod parsing {
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        primitives::SdkBody,
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{Borrowed, File};
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(serde::Deserialize, serde::Serialize, yoke::Yokeable, Eq, PartialEq, Debug)]
+    struct MyStruct<'a> {
+        key: &'a str,
+    }
+
+    fn response(body: Vec<u8>) -> ReplayEvent {
+        ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .body(SdkBody::from(body))
+                .unwrap(),
+        )
+    }
+
+    #[cfg(feature = "cbor")]
+    #[tokio::test]
+    async fn test_parsing_borrowed_cbor() {
+        use conditional_s3_fetch::Cbor;
+
+        let body = cbor4ii::serde::to_vec(vec![], &MyStruct { key: "value" }).unwrap();
+        let replay_client = StaticReplayClient::new(vec![response(body)]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::<Borrowed<Cbor<MyStruct<'static>>>>::loaded(
+            "test-bucket",
+            "test-prefix",
+            &client,
+        )
+        .await
+        .expect("Failed to fetch file");
+
+        replay_client.assert_requests_match(&[]);
+        let content = file.as_content().expect("File is loaded");
+        assert_eq!(content.get(), &MyStruct { key: "value" });
+
+        let buffer = content.backing_cart().bytes().as_ptr_range();
+        assert!(buffer.contains(&content.get().key.as_ptr()));
+    }
+
+    #[cfg(feature = "json")]
+    #[tokio::test]
+    async fn test_parsing_borrowed_json() {
+        use conditional_s3_fetch::Json;
+
+        let body = br#"{"key": "value"}"#.to_vec();
+        let replay_client = StaticReplayClient::new(vec![response(body)]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::<Borrowed<Json<MyStruct<'static>>>>::loaded(
+            "test-bucket",
+            "test-prefix",
+            &client,
+        )
+        .await
+        .expect("Failed to fetch file");
+
+        replay_client.assert_requests_match(&[]);
+        let content = file.as_content().expect("File is loaded");
+        assert_eq!(content.get(), &MyStruct { key: "value" });
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_parsing_failure() {
+        use conditional_s3_fetch::{Cbor, Parse};
+
+        let parsed = Borrowed::<Cbor<MyStruct<'static>>>::parse("bad data".into());
+        assert!(parsed.is_err());
+    }
+}
@@ -98,4 +98,23 @@ mod parsing {
             Err(conditional_s3_fetch::Error::ParseError(_))
         ));
     }
+
+    #[test]
+    fn test_parsing_failure_location() {
+        use conditional_s3_fetch::{DeserializeError, Parse};
+
+        #[derive(serde::Serialize)]
+        struct Invalid {
+            key: u32,
+        }
+
+        let data = cbor4ii::serde::to_vec(vec![], &Invalid { key: 1 }).unwrap();
+        let error = Cbor::<MyStruct>::parse(data.into()).expect_err("Key is not a string");
+        let error = error
+            .downcast_ref::<DeserializeError>()
+            .expect("Expected a deserialize error");
+
+        assert_eq!(error.path(), "key");
+        assert_eq!(error.line(), None);
+    }
 }
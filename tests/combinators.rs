@@ -0,0 +1,168 @@
+mod common;
+
+use conditional_s3_fetch::{
+    BoxedResult, Fallback, Lines, Map, OrDefault, ParseContext, StreamParse, Validated, Validator,
+};
+
+fn feed_chunks<P: StreamParse>(parser: &P, chunks: &[&'static str]) -> BoxedResult<P::Output> {
+    let context = ParseContext::new("test-bucket", "test-prefix");
+    let mut state = parser.start(&context);
+    for chunk in chunks {
+        parser.feed(&mut state, bytes::Bytes::from_static(chunk.as_bytes()))?;
+    }
+    parser.finish(state, &context)
+}
+
+struct NotEmpty;
+
+impl Validator<Vec<String>> for NotEmpty {
+    fn validate(&self, lines: &Vec<String>, _context: &ParseContext) -> BoxedResult<()> {
+        if lines.is_empty() {
+            return Err("Feed must not be empty".into());
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_map() {
+    let parser = Map::new(Lines, |lines: Vec<String>| lines.len());
+    assert_eq!(feed_chunks(&parser, &["a\nb", "\nc\n"]).unwrap(), 3);
+}
+
+#[test]
+fn test_validated() {
+    let parser = Validated::new(Lines, NotEmpty);
+    assert_eq!(feed_chunks(&parser, &["a\n"]).unwrap(), vec!["a"]);
+
+    let error = feed_chunks(&parser, &[]).expect_err("Empty feed should be rejected");
+    assert_eq!(error.to_string(), "Feed must not be empty");
+}
+
+#[test]
+fn test_or_default() {
+    let parser = OrDefault::new(Validated::new(Lines, NotEmpty));
+    assert_eq!(feed_chunks(&parser, &[]).unwrap(), Vec::<String>::new());
+    assert_eq!(
+        feed_chunks(&parser, &["", ""]).unwrap(),
+        Vec::<String>::new()
+    );
+    assert_eq!(feed_chunks(&parser, &["a"]).unwrap(), vec!["a"]);
+}
+
+#[test]
+fn test_fallback() {
+    let parser = Fallback::new(
+        Validated::new(String::new(), |text: &String| {
+            if text.starts_with('#') {
+                return Err("Comments are not supported".into());
+            }
+            Ok(())
+        }),
+        Map::new(Lines, |lines: Vec<String>| lines.join(" ")),
+    );
+    assert_eq!(
+        feed_chunks(&parser, &["plain\ntext"]).unwrap(),
+        "plain\ntext"
+    );
+    assert_eq!(feed_chunks(&parser, &["# a\n", "b"]).unwrap(), "# a b");
+}
+
+#[cfg(all(feature = "cbor", any(feature = "json", feature = "simd-json")))]
+mod parsing {
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        primitives::SdkBody,
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{
+        combinators::FallbackError, BoxedResult, Cbor, Fallback, File, Json, ParseContext,
+        Validated, Validator,
+    };
+
+    use crate::common::{request, response};
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    struct Settings {
+        port: u16,
+    }
+
+    #[derive(Default)]
+    struct NonZeroPort;
+
+    impl Validator<Settings> for NonZeroPort {
+        fn validate(&self, settings: &Settings, _context: &ParseContext) -> BoxedResult<()> {
+            if settings.port == 0 {
+                return Err("Port must not be zero".into());
+            }
+            Ok(())
+        }
+    }
+
+    type Parser = Validated<Fallback<Cbor<Settings>, Json<Settings>>, NonZeroPort>;
+
+    #[tokio::test]
+    async fn test_composed_combinators() {
+        let cbor = cbor4ii::serde::to_vec(vec![], &Settings { port: 8080 }).unwrap();
+
+        let replay_client = StaticReplayClient::new(vec![
+            ReplayEvent::new(request(None), response("\"1\"", SdkBody::from(cbor))),
+            ReplayEvent::new(
+                request(Some("\"1\"")),
+                response("\"2\"", SdkBody::from(r#"{"port": 9090}"#)),
+            ),
+            ReplayEvent::new(
+                request(Some("\"2\"")),
+                response("\"3\"", SdkBody::from(r#"{"port": 0}"#)),
+            ),
+            ReplayEvent::new(
+                request(Some("\"3\"")),
+                response("\"4\"", SdkBody::from("port = 1")),
+            ),
+        ]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::<Parser>::loaded("test-bucket", "test-prefix", &client)
+            .await
+            .expect("Failed to fetch file");
+        assert_eq!(file.as_content().map(|c| c.port), Some(8080));
+
+        let file = file
+            .fetch(&client)
+            .await
+            .expect("Failed to fetch file")
+            .expect("File is modified");
+        assert_eq!(file.as_content().map(|c| c.port), Some(9090));
+
+        let error = file.fetch(&client).await.expect_err("Port is zero");
+        assert_eq!(
+            error.to_string(),
+            "Rejected version \"3\": Port must not be zero"
+        );
+
+        let error = file.fetch(&client).await.expect_err("Content is invalid");
+        let conditional_s3_fetch::Error::ParseError(error) = error else {
+            panic!("Expected a parse error, got {error:?}");
+        };
+        assert!(error.downcast_ref::<FallbackError>().is_some());
+    }
+}
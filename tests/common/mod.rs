@@ -0,0 +1,49 @@
+//! Replay fixtures shared by the integration tests
+#![allow(dead_code)]
+
+use aws_sdk_s3::primitives::SdkBody;
+
+/// `GetObject` request for `test-prefix` on `test-bucket`, with the `If-None-Match` header if an `ETag` is given
+pub fn request(etag: Option<&str>) -> http::Request<SdkBody> {
+    request_key("test-prefix", etag)
+}
+
+/// `GetObject` request for `key` on `test-bucket`, with the `If-None-Match` header if an `ETag` is given
+pub fn request_key(key: &str, etag: Option<&str>) -> http::Request<SdkBody> {
+    request_object("test-bucket", key, etag)
+}
+
+/// `GetObject` request for `key` on `bucket`, with the `If-None-Match` header if an `ETag` is given
+pub fn request_object(bucket: &str, key: &str, etag: Option<&str>) -> http::Request<SdkBody> {
+    let mut builder = http::Request::builder().method("GET").uri(format!(
+        "https://{bucket}.s3.us-east-1.amazonaws.com/{key}?x-id=GetObject"
+    ));
+    if let Some(etag) = etag {
+        builder = builder.header("If-None-Match", etag);
+    }
+    builder.body(SdkBody::empty()).unwrap()
+}
+
+pub fn response(etag: &str, body: impl Into<SdkBody>) -> http::Response<SdkBody> {
+    http::Response::builder()
+        .status(200)
+        .header("ETag", etag)
+        .body(body.into())
+        .unwrap()
+}
+
+pub fn not_modified() -> http::Response<SdkBody> {
+    http::Response::builder()
+        .status(304)
+        .body(SdkBody::empty())
+        .unwrap()
+}
+
+pub fn not_found() -> http::Response<SdkBody> {
+    http::Response::builder()
+        .status(404)
+        .body(SdkBody::from(
+            "<Error><Code>NoSuchKey</Code><Message>The specified key does not exist.</Message></Error>",
+        ))
+        .unwrap()
+}
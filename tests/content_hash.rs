@@ -0,0 +1,127 @@
+mod common;
+
+use aws_sdk_s3::{
+    config::{Credentials, Region},
+    Client, Config,
+};
+use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+use conditional_s3_fetch::{Completion, Executor, File, Offload, Task};
+
+use crate::common::{not_modified, request, response};
+
+fn test_client(replay_client: StaticReplayClient) -> Client {
+    Client::from_conf(
+        Config::builder()
+            .behavior_version_latest()
+            .credentials_provider(Credentials::new(
+                "ATESTCLIENT",
+                "astestsecretkey",
+                Some("atestsessiontoken".to_string()),
+                None,
+                "",
+            ))
+            .region(Region::new("us-east-1"))
+            .http_client(replay_client)
+            .build(),
+    )
+}
+
+fn replay_client() -> StaticReplayClient {
+    StaticReplayClient::new(vec![
+        ReplayEvent::new(request(None), response("\"1\"", "hello")),
+        ReplayEvent::new(request(Some("\"1\"")), response("\"2\"", "hello")),
+        ReplayEvent::new(request(Some("\"2\"")), not_modified()),
+        ReplayEvent::new(request(Some("\"2\"")), response("\"3\"", "world")),
+    ])
+}
+
+/// Executor running the tasks immediately, on the current thread
+struct InlineExecutor;
+
+impl Executor for InlineExecutor {
+    fn spawn_blocking(&self, task: Task) -> Completion {
+        task();
+        Box::pin(async {})
+    }
+}
+
+#[tokio::test]
+async fn test_identical_content_is_unchanged() {
+    let replay_client = replay_client();
+    let client = test_client(replay_client.clone());
+
+    let file = File::<String>::unloaded("test-bucket", "test-prefix")
+        .with_content_hashing()
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is loaded");
+
+    let identical = file.fetch(&client).await.expect("Failed to fetch file");
+    assert!(identical.is_none());
+
+    let unchanged = file.fetch(&client).await.expect("Failed to fetch file");
+    assert!(unchanged.is_none());
+
+    let file = file
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is modified");
+    replay_client.assert_requests_match(&[]);
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("world"));
+}
+
+#[tokio::test]
+async fn test_identical_content_is_unchanged_when_offloaded() {
+    let replay_client = replay_client();
+    let client = test_client(replay_client.clone());
+    let offload = Offload::new(0, InlineExecutor);
+
+    let file = File::<String>::unloaded("test-bucket", "test-prefix")
+        .with_content_hashing()
+        .fetch_offloaded(&client, &offload)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is loaded");
+
+    let identical = file
+        .fetch_offloaded(&client, &offload)
+        .await
+        .expect("Failed to fetch file");
+    assert!(identical.is_none());
+
+    let unchanged = file
+        .fetch_offloaded(&client, &offload)
+        .await
+        .expect("Failed to fetch file");
+    assert!(unchanged.is_none());
+
+    let file = file
+        .fetch_offloaded(&client, &offload)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is modified");
+    replay_client.assert_requests_match(&[]);
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("world"));
+}
+
+#[tokio::test]
+async fn test_identical_content_is_reloaded_without_hashing() {
+    let replay_client = StaticReplayClient::new(vec![
+        ReplayEvent::new(request(None), response("\"1\"", "hello")),
+        ReplayEvent::new(request(Some("\"1\"")), response("\"2\"", "hello")),
+    ]);
+    let client = test_client(replay_client.clone());
+
+    let file = File::<String>::unloaded("test-bucket", "test-prefix")
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is loaded");
+    let reloaded = file.fetch(&client).await.expect("Failed to fetch file");
+
+    replay_client.assert_requests_match(&[]);
+    assert!(reloaded.is_some());
+}
@@ -0,0 +1,150 @@
+use aws_sdk_s3::{
+    config::{Credentials, Region},
+    primitives::SdkBody,
+    Client, Config,
+};
+use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+use conditional_s3_fetch::{BoxedResult, File, ParseContext, ParseWithContext};
+
+fn test_client(replay_client: StaticReplayClient) -> Client {
+    Client::from_conf(
+        Config::builder()
+            .behavior_version_latest()
+            .credentials_provider(Credentials::new(
+                "ATESTCLIENT",
+                "astestsecretkey",
+                Some("atestsessiontoken".to_string()),
+                None,
+                "",
+            ))
+            .region(Region::new("us-east-1"))
+            .http_client(replay_client)
+            .build(),
+    )
+}
+
+/// Keeps the context received by the parser, to be inspected by the tests
+#[derive(Default)]
+struct CaptureContext;
+
+impl ParseWithContext for CaptureContext {
+    type Output = (ParseContext, String);
+
+    fn parse_with_context(
+        &self,
+        bytes: bytes::Bytes,
+        context: &ParseContext,
+    ) -> BoxedResult<Self::Output> {
+        Ok((context.clone(), String::from_utf8(bytes.to_vec())?))
+    }
+}
+
+/// Parser configured at runtime, without a `Default` implementation
+struct Prefixed(String);
+
+impl ParseWithContext for Prefixed {
+    type Output = String;
+
+    fn parse_with_context(
+        &self,
+        bytes: bytes::Bytes,
+        _context: &ParseContext,
+    ) -> BoxedResult<Self::Output> {
+        Ok(format!("{}{}", self.0, String::from_utf8(bytes.to_vec())?))
+    }
+}
+
+#[tokio::test]
+async fn test_parsing_with_context() {
+    let req1 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"123\"")
+            .header("Content-Type", "text/plain")
+            .header("x-amz-version-id", "v1")
+            .header("x-amz-meta-schema-version", "2")
+            .body(SdkBody::from("hello"))
+            .unwrap(),
+    );
+    let replay_client = StaticReplayClient::new(vec![req1]);
+    let client = test_client(replay_client.clone());
+
+    let file = File::<CaptureContext>::loaded("test-bucket", "test-prefix", &client)
+        .await
+        .expect("Failed to fetch file");
+
+    replay_client.assert_requests_match(&[]);
+    let (context, body) = file.into_inner().expect("File is loaded");
+    assert_eq!(body, "hello");
+    assert_eq!(
+        context,
+        ParseContext::new("test-bucket", "test-prefix")
+            .with_etag("\"123\"")
+            .with_version_id("v1")
+            .with_content_type("text/plain")
+            .with_metadata("schema-version", "2")
+    );
+}
+
+#[test]
+fn test_parse_implementations_ignore_context() {
+    let context = ParseContext::new("test-bucket", "test-prefix");
+
+    let parsed = String::new()
+        .parse_with_context("hello".into(), &context)
+        .unwrap();
+    assert_eq!(parsed, "hello");
+}
+
+#[tokio::test]
+async fn test_parsing_with_parser_instance() {
+    let req1 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"123\"")
+            .body(SdkBody::from("hello"))
+            .unwrap(),
+    );
+    let req2 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .header("If-None-Match", "\"123\"")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"456\"")
+            .body(SdkBody::from("world"))
+            .unwrap(),
+    );
+    let replay_client = StaticReplayClient::new(vec![req1, req2]);
+    let client = test_client(replay_client.clone());
+
+    let file = File::with_parser("test-bucket", "test-prefix", Prefixed("> ".into()));
+    let file = file
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is modified");
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("> hello"));
+
+    let file = file
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is modified");
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("> world"));
+    assert_eq!(file.parser().0, "> ");
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use std::sync::Arc;
+
+use aws_sdk_s3::{
+    config::{Credentials, Region},
+    primitives::SdkBody,
+    Client, Config,
+};
+use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+use conditional_s3_fetch::{DeletionPolicy, Error, File, Refresh};
+
+use crate::common::{not_found, request, response};
+
+fn test_client(replay_client: StaticReplayClient) -> Client {
+    Client::from_conf(
+        Config::builder()
+            .behavior_version_latest()
+            .credentials_provider(Credentials::new(
+                "ATESTCLIENT",
+                "astestsecretkey",
+                Some("atestsessiontoken".to_string()),
+                None,
+                "",
+            ))
+            .region(Region::new("us-east-1"))
+            .http_client(replay_client)
+            .build(),
+    )
+}
+
+#[tokio::test]
+async fn test_deleted_object_keeps_content() {
+    let replay_client = StaticReplayClient::new(vec![
+        ReplayEvent::new(request(None), response("\"123\"", "hello")),
+        ReplayEvent::new(request(Some("\"123\"")), not_found()),
+    ]);
+    let client = test_client(replay_client.clone());
+
+    let mut file = File::<String>::loaded("test-bucket", "test-prefix", &client)
+        .await
+        .expect("Failed to fetch file");
+
+    let error = file.refresh(&client).await.expect_err("Object is deleted");
+    assert!(matches!(error, Error::NotFound { .. }));
+    assert!(!file.is_deleted());
+    assert_eq!(file.as_content().map(|f| f.as_str()), Some("hello"));
+
+    replay_client.assert_requests_match(&[]);
+}
+
+#[tokio::test]
+async fn test_deleted_object_reappears() {
+    let replay_client = StaticReplayClient::new(vec![
+        ReplayEvent::new(request(None), response("\"123\"", "hello")),
+        ReplayEvent::new(request(Some("\"123\"")), not_found()),
+        ReplayEvent::new(request(None), not_found()),
+        ReplayEvent::new(request(None), response("\"123\"", "hello")),
+    ]);
+    let client = test_client(replay_client.clone());
+
+    let mut file = File::<String>::unloaded("test-bucket", "test-prefix")
+        .with_deletion_policy(DeletionPolicy::MarkDeleted);
+    file.refresh(&client).await.expect("Failed to fetch file");
+
+    let deleted = file.refresh(&client).await.expect("Object is deleted");
+    let Refresh::Deleted {
+        previous: Some(previous),
+    } = deleted
+    else {
+        panic!("File should be deleted, got {deleted:?}");
+    };
+    assert_eq!(previous.as_str(), "hello");
+    assert!(file.is_deleted());
+    assert_eq!(file.as_content(), None);
+
+    let unchanged = file.refresh(&client).await.expect("Object is deleted");
+    assert_eq!(unchanged, Refresh::Unchanged);
+
+    let reappeared = file.refresh(&client).await.expect("Failed to fetch file");
+    assert_eq!(reappeared, Refresh::FirstLoad);
+    assert!(!file.is_deleted());
+    assert_eq!(file.as_content().map(|f| f.as_str()), Some("hello"));
+
+    replay_client.assert_requests_match(&[]);
+}
+
+#[tokio::test]
+async fn test_deleted_object_fallback() {
+    let replay_client = StaticReplayClient::new(vec![ReplayEvent::new(request(None), not_found())]);
+    let client = test_client(replay_client.clone());
+
+    let file = File::<String>::unloaded("test-bucket", "test-prefix")
+        .with_deletion_policy(DeletionPolicy::Fallback(Arc::new("default".into())))
+        .fetch(&client)
+        .await
+        .expect("Object is deleted")
+        .expect("File is deleted");
+
+    replay_client.assert_requests_match(&[]);
+    assert!(file.is_deleted());
+    let content = file.as_content().expect("Fallback content");
+    assert_eq!(content.etag(), "");
+    assert_eq!(content.as_str(), "default");
+}
+
+#[tokio::test]
+async fn test_missing_bucket_is_not_a_deletion() {
+    let replay_client = StaticReplayClient::new(vec![
+        ReplayEvent::new(request(None), response("\"123\"", "hello")),
+        ReplayEvent::new(
+            request(Some("\"123\"")),
+            http::Response::builder()
+                .status(404)
+                .body(SdkBody::from(
+                    "<Error><Code>NoSuchBucket</Code><Message>The specified bucket does not exist.</Message></Error>",
+                ))
+                .unwrap(),
+        ),
+    ]);
+    let client = test_client(replay_client.clone());
+
+    let mut file = File::<String>::loaded("test-bucket", "test-prefix", &client)
+        .await
+        .expect("Failed to fetch file")
+        .with_deletion_policy(DeletionPolicy::MarkDeleted);
+
+    let error = file.refresh(&client).await.expect_err("Bucket is missing");
+    assert!(matches!(error, Error::SdkError(_)));
+    assert!(!file.is_deleted());
+    assert_eq!(file.as_content().map(|f| f.as_str()), Some("hello"));
+
+    replay_client.assert_requests_match(&[]);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use std::cell::Cell;
+
+use aws_sdk_s3::{
+    config::{Credentials, Region},
+    Client, Config,
+};
+use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+use conditional_s3_fetch::{Derived, File};
+
+use crate::common::{request_key, response};
+
+fn test_client(replay_client: StaticReplayClient) -> Client {
+    Client::from_conf(
+        Config::builder()
+            .behavior_version_latest()
+            .credentials_provider(Credentials::new(
+                "ATESTCLIENT",
+                "astestsecretkey",
+                Some("atestsessiontoken".to_string()),
+                None,
+                "",
+            ))
+            .region(Region::new("us-east-1"))
+            .http_client(replay_client)
+            .build(),
+    )
+}
+
+#[tokio::test]
+async fn test_computing_only_when_inputs_change() {
+    let replay_client = StaticReplayClient::new(vec![
+        ReplayEvent::new(request_key("words", None), response("\"1\"", "b a")),
+        ReplayEvent::new(request_key("prefix", None), response("\"2\"", "> ")),
+        ReplayEvent::new(
+            request_key("words", Some("\"1\"")),
+            response("\"3\"", "c b a"),
+        ),
+    ]);
+    let client = test_client(replay_client.clone());
+    let computed = Cell::new(0);
+    let index = |(words, prefix): (&String, &String)| {
+        computed.set(computed.get() + 1);
+        let mut words: Vec<_> = words.split(' ').map(|w| format!("{prefix}{w}")).collect();
+        words.sort_unstable();
+        words
+    };
+
+    let words = File::<String>::unloaded("test-bucket", "words");
+    let prefix = File::<String>::unloaded("test-bucket", "prefix");
+    let derived = Derived::new();
+    assert!(derived.update((&words, &prefix), index).is_none());
+
+    let words = words.fetch(&client).await.unwrap().unwrap();
+    let prefix = prefix.fetch(&client).await.unwrap().unwrap();
+    let derived = derived
+        .update((&words, &prefix), index)
+        .expect("Inputs are loaded");
+    assert_eq!(
+        derived.as_content(),
+        Some(&vec!["> a".to_string(), "> b".to_string()])
+    );
+    assert_eq!(derived.etags(), ["\"1\"", "\"2\""]);
+    assert!(derived.update((&words, &prefix), index).is_none());
+    assert_eq!(computed.get(), 1);
+
+    let words = words.fetch(&client).await.unwrap().unwrap();
+    let derived = derived
+        .update((&words, &prefix), index)
+        .expect("Words changed");
+    replay_client.assert_requests_match(&[]);
+    assert_eq!(computed.get(), 2);
+    assert_eq!(
+        derived.into_inner(),
+        Some(vec![
+            "> a".to_string(),
+            "> b".to_string(),
+            "> c".to_string()
+        ])
+    );
+}
+
+#[test]
+fn test_computing_again_for_content_not_fetched_from_s3() {
+    let file = File::<String>::with_default("test-bucket", "words", "a".into());
+    let derived = Derived::new()
+        .update(&file, |words: &String| words.clone())
+        .expect("Default content is loaded");
+
+    let file = File::<String>::with_default("test-bucket", "words", "b".into());
+    let derived = derived
+        .update(&file, |words: &String| words.clone())
+        .expect("Content without ETag is computed again");
+    assert_eq!(derived.as_content().map(String::as_str), Some("b"));
+}
+
+#[test]
+fn test_keeping_value_for_the_same_content_not_fetched_from_s3() {
+    let computed = Cell::new(0);
+    let words = |words: &String| {
+        computed.set(computed.get() + 1);
+        words.clone()
+    };
+
+    let file = File::<String>::with_default("test-bucket", "words", "a".into());
+    let derived = Derived::new()
+        .update(&file, words)
+        .expect("Default content is loaded");
+    assert_eq!(derived.etags(), [""]);
+    assert!(derived.update(&file, words).is_none());
+    assert!(derived.update(&file.clone(), words).is_none());
+    assert_eq!(computed.get(), 1);
+}
@@ -0,0 +1,125 @@
+#[cfg(feature = "dotenv")]
+mod parsing {
+    use std::ops::Deref;
+
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        primitives::SdkBody,
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{DotEnv, File, OrderedMap, Parse};
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(serde::Deserialize, Eq, PartialEq, Debug)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    struct MyStruct {
+        service_name: String,
+        port: u16,
+        debug: bool,
+        hosts: Vec<String>,
+        greeting: String,
+        raw: String,
+    }
+
+    const ENV: &str = r#"
+# Service configuration
+export SERVICE_NAME=payments # inline comment
+PORT = 8080
+DEBUG=true
+HOSTS=a.example.com, b.example.com
+GREETING="hello
+\"world\"\t!"
+RAW='no \n escapes'
+"#;
+
+    #[tokio::test]
+    async fn test_parsing_dotenv() {
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .body(SdkBody::from(ENV))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::<DotEnv<MyStruct>>::loaded("test-bucket", "test-prefix", &client)
+            .await
+            .expect("Failed to fetch file");
+
+        replay_client.assert_requests_match(&[]);
+        assert_eq!(
+            file.as_content().map(|f| f.deref()),
+            Some(&MyStruct {
+                service_name: "payments".to_string(),
+                port: 8080,
+                debug: true,
+                hosts: vec!["a.example.com".to_string(), "b.example.com".to_string()],
+                greeting: "hello\n\"world\"\t!".to_string(),
+                raw: "no \\n escapes".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parsing_ordered_map() {
+        let map = DotEnv::<OrderedMap>::parse("B=2\nA=1\nB=3\n".into()).unwrap();
+
+        assert_eq!(
+            map.into_inner(),
+            vec![
+                ("B".to_string(), "3".to_string()),
+                ("A".to_string(), "1".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parsing_failure() {
+        assert!(DotEnv::<OrderedMap>::parse("KEY=\"unterminated\n".into()).is_err());
+        assert!(DotEnv::<OrderedMap>::parse("not an entry\n".into()).is_err());
+    }
+
+    #[test]
+    fn test_parsing_failure_location() {
+        use conditional_s3_fetch::DeserializeError;
+
+        #[derive(serde::Deserialize, Debug)]
+        #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+        #[allow(dead_code)]
+        struct Settings {
+            port: u16,
+        }
+
+        let error =
+            DotEnv::<Settings>::parse("PORT=high\n".into()).expect_err("Port is not a number");
+        let error = error
+            .downcast_ref::<DeserializeError>()
+            .expect("Expected a deserialize error");
+        assert_eq!(error.path(), "PORT");
+    }
+}
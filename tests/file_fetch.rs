@@ -5,7 +5,7 @@ use aws_sdk_s3::{
 };
 use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
 
-use conditional_s3_fetch::File;
+use conditional_s3_fetch::{File, Refresh};
 
 fn test_client(replay_client: StaticReplayClient) -> Client {
     Client::from_conf(
@@ -92,6 +92,71 @@ async fn test_refetching_file_no_modified() {
     replay_client.assert_requests_match(&[]);
 }
 
+#[tokio::test]
+async fn test_clones_share_loaded_content() {
+    let req1 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"123\"")
+            .body(SdkBody::from("hello"))
+            .unwrap(),
+    );
+    let req2 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .header("If-None-Match", "\"123\"")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"125\"")
+            .body(SdkBody::from("bye"))
+            .unwrap(),
+    );
+    let replay_client = StaticReplayClient::new(vec![req1, req2]);
+    let client = test_client(replay_client.clone());
+
+    let mut file = File::<String>::loaded("test-bucket", "test-prefix", &client)
+        .await
+        .expect("Failed to fetch file");
+    let content = file.as_content().expect("File is loaded");
+    let snapshot = content.shared();
+    assert!(std::sync::Arc::ptr_eq(
+        &snapshot,
+        &file.clone().as_content().expect("File is loaded").shared()
+    ));
+    assert_eq!(content.etag(), "\"123\"");
+
+    file = file
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is modified");
+    replay_client.assert_requests_match(&[]);
+    assert_eq!(snapshot.as_str(), "hello");
+    assert_eq!(
+        file.clone().into_shared().as_deref().map(String::as_str),
+        Some("bye")
+    );
+
+    let content = file.as_content().cloned().expect("File is loaded");
+    let content = content
+        .into_inner()
+        .expect_err("Content is shared by the file");
+    let file = file
+        .into_inner()
+        .expect_err("Content is shared by its clone");
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("bye"));
+    drop(file);
+    assert_eq!(content.into_inner().ok().as_deref(), Some("bye"));
+}
+
 #[tokio::test]
 async fn test_refetching_file_after_being_modified() {
     let req1 = ReplayEvent::new(
@@ -158,6 +223,75 @@ async fn test_refetching_file_after_being_modified() {
     replay_client.assert_requests_match(&[]);
 }
 
+#[tokio::test]
+async fn test_refreshing_file_in_place() {
+    let req1 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"123\"")
+            .body(SdkBody::from("hello"))
+            .unwrap(),
+    );
+
+    let req2 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .header("If-None-Match", "\"123\"")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(304)
+            .body(SdkBody::empty())
+            .unwrap(),
+    );
+
+    let req3 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .header("If-None-Match", "\"123\"")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"125\"")
+            .body(SdkBody::from("bye"))
+            .unwrap(),
+    );
+
+    let replay_client = StaticReplayClient::new(vec![req1, req2, req3]);
+    let client = test_client(replay_client.clone());
+
+    let mut file = File::<String>::unloaded("test-bucket", "test-prefix");
+
+    let first = file.refresh(&client).await.expect("Failed to fetch file");
+    assert_eq!(first, Refresh::FirstLoad);
+    assert_eq!(file.as_content().map(|f| f.as_str()), Some("hello"));
+
+    let unchanged = file.refresh(&client).await.expect("Failed to fetch file");
+    assert!(!unchanged.is_changed());
+
+    let updated = file.refresh(&client).await.expect("Failed to fetch file");
+    let Refresh::Updated {
+        previous_etag,
+        previous,
+    } = updated
+    else {
+        panic!("File should be updated, got {updated:?}");
+    };
+    assert_eq!(previous_etag, "\"123\"");
+    assert_eq!(previous.as_str(), "hello");
+    assert_eq!(file.as_content().map(|f| f.as_str()), Some("bye"));
+
+    replay_client.assert_requests_match(&[]);
+}
+
 mod parsing {
     use std::ops::Deref;
 
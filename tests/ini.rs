@@ -0,0 +1,118 @@
+#[cfg(feature = "ini")]
+mod parsing {
+    use std::ops::Deref;
+
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        primitives::SdkBody,
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{File, Ini, OrderedMap, Parse};
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(serde::Deserialize, Eq, PartialEq, Debug)]
+    struct Database {
+        host: String,
+        port: u16,
+        banner: String,
+    }
+
+    #[derive(serde::Deserialize, Eq, PartialEq, Debug)]
+    struct MyStruct {
+        name: String,
+        database: Database,
+        motd: String,
+    }
+
+    const INI: &str = r#"
+; global entries
+name = payments
+
+[database]
+host = db.example.com
+port: 5432
+banner = "quoted \"value\""
+
+[messages]
+unused = ignored
+
+[database]
+host = replica.example.com
+"#;
+
+    #[tokio::test]
+    async fn test_parsing_ini() {
+        let body = format!("motd = first\n  second\n{INI}");
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .body(SdkBody::from(body))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::<Ini<MyStruct>>::loaded("test-bucket", "test-prefix", &client)
+            .await
+            .expect("Failed to fetch file");
+
+        replay_client.assert_requests_match(&[]);
+        assert_eq!(
+            file.as_content().map(|f| f.deref()),
+            Some(&MyStruct {
+                name: "payments".to_string(),
+                database: Database {
+                    host: "replica.example.com".to_string(),
+                    port: 5432,
+                    banner: "quoted \"value\"".to_string(),
+                },
+                motd: "first\nsecond".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parsing_ordered_map() {
+        let body = "[b]\nkey = 1\n[a]\nkey = 2\n";
+        let map = Ini::<OrderedMap<OrderedMap>>::parse(body.into()).unwrap();
+
+        assert_eq!(
+            map.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            vec!["b", "a"]
+        );
+        assert_eq!(
+            map.get("a").and_then(|section| section.get("key")),
+            Some(&"2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parsing_failure() {
+        assert!(Ini::<OrderedMap<OrderedMap>>::parse("[unterminated\n".into()).is_err());
+        assert!(Ini::<OrderedMap<OrderedMap>>::parse("[s]\nkey = \"open\n".into()).is_err());
+    }
+}
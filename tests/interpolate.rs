@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use aws_sdk_s3::{
+    config::{Credentials, Region},
+    primitives::SdkBody,
+    Client, Config,
+};
+use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+use conditional_s3_fetch::{
+    interpolate::InterpolationError, Error, File, Interpolated, ParseContext, StreamParse,
+};
+
+fn test_client(replay_client: StaticReplayClient) -> Client {
+    Client::from_conf(
+        Config::builder()
+            .behavior_version_latest()
+            .credentials_provider(Credentials::new(
+                "ATESTCLIENT",
+                "astestsecretkey",
+                Some("atestsessiontoken".to_string()),
+                None,
+                "",
+            ))
+            .region(Region::new("us-east-1"))
+            .http_client(replay_client)
+            .build(),
+    )
+}
+
+fn parser() -> Interpolated<String> {
+    Interpolated::new(String::new()).with_variables(HashMap::from([
+        ("REGION".to_string(), "eu-west-1".to_string()),
+        ("EMPTY".to_string(), String::new()),
+    ]))
+}
+
+fn expand(parser: &Interpolated<String>, chunks: &[&'static str]) -> Result<String, String> {
+    let context = ParseContext::new("test-bucket", "test-prefix");
+    let mut state = parser.start(&context);
+    for chunk in chunks {
+        parser.feed(&mut state, chunk.as_bytes().into()).unwrap();
+    }
+    parser.finish(state, &context).map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_expanding_placeholders() {
+    let parser = parser();
+
+    assert_eq!(
+        expand(
+            &parser,
+            &["region=${REG", "ION}\n", "name=${SERVICE_NAME:-payments}"]
+        ),
+        Ok("region=eu-west-1\nname=payments".to_string())
+    );
+    assert_eq!(
+        expand(&parser, &["${EMPTY:-fallback} ${EMPTY} ${MISSING:-}"]),
+        Ok("fallback  ".to_string())
+    );
+    assert_eq!(
+        expand(&parser, &["$${REGION} costs $5, see $ref"]),
+        Ok("${REGION} costs $5, see $ref".to_string())
+    );
+}
+
+#[test]
+fn test_expanding_failures() {
+    let parser = parser();
+    let error = |chunks| {
+        let context = ParseContext::new("test-bucket", "test-prefix");
+        let mut state = parser.start(&context);
+        for chunk in chunks {
+            parser.feed(&mut state, chunk).unwrap();
+        }
+        let error = parser
+            .finish(state, &context)
+            .expect_err("Expected a failure");
+        error.downcast_ref::<InterpolationError>().cloned()
+    };
+
+    assert_eq!(
+        error(vec!["a\nb=${SERVICE_NAME}".into()]),
+        Some(InterpolationError::Undefined {
+            name: "SERVICE_NAME".to_string(),
+            line: 2
+        })
+    );
+    assert_eq!(
+        error(vec!["${REGION".into()]),
+        Some(InterpolationError::Unterminated { line: 1 })
+    );
+    assert_eq!(
+        error(vec!["${NOT A NAME}".into()]),
+        Some(InterpolationError::InvalidName {
+            name: "NOT A NAME".to_string(),
+            line: 1
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_expanding_from_environment() {
+    std::env::set_var("CONDITIONAL_S3_FETCH_TEST_REGION", "us-east-2");
+
+    let req1 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"123\"")
+            .body(SdkBody::from(
+                "region=${CONDITIONAL_S3_FETCH_TEST_REGION} zone=${CONDITIONAL_S3_FETCH_TEST_ZONE}",
+            ))
+            .unwrap(),
+    );
+    let replay_client = StaticReplayClient::new(vec![req1]);
+    let client = test_client(replay_client.clone());
+
+    let error = File::<Interpolated<String>>::unloaded("test-bucket", "test-prefix")
+        .fetch(&client)
+        .await
+        .expect_err("Zone is not defined");
+
+    replay_client.assert_requests_match(&[]);
+    let Error::ParseError(error) = error else {
+        panic!("Expected a parse error, got {error:?}");
+    };
+    assert_eq!(
+        error.to_string(),
+        "Undefined variable CONDITIONAL_S3_FETCH_TEST_ZONE at line 1"
+    );
+}
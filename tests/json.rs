@@ -90,4 +90,107 @@ mod parsing {
             Err(conditional_s3_fetch::Error::ParseError(_))
         ));
     }
+
+    #[derive(serde::Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Routes {
+        routes: Vec<Route>,
+    }
+
+    #[derive(serde::Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Route {
+        timeout_ms: u32,
+    }
+
+    #[test]
+    fn test_parsing_failure_location() {
+        use conditional_s3_fetch::{DeserializeError, Parse};
+
+        let data = "{\n  \"routes\": [\n    { \"timeout_ms\": 10 },\n    { \"timeout_ms\": \"slow\" }\n  ]\n}";
+        let error = Json::<Routes>::parse(data.into()).expect_err("Timeout is not a number");
+        let error = error
+            .downcast_ref::<DeserializeError>()
+            .expect("Expected a deserialize error");
+
+        assert_eq!(error.path(), "routes[1].timeout_ms");
+        #[cfg(feature = "json")]
+        {
+            assert_eq!((error.line(), error.column()), (Some(4), Some(26)));
+            assert_eq!(
+                error.to_string(),
+                "routes[1].timeout_ms: invalid type: string \"slow\", expected u32 at line 4 column 26"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parsing_syntax_error_location() {
+        use conditional_s3_fetch::{DeserializeError, Parse};
+
+        let data = "{\n  \"routes\": [\n    { \"timeout_ms\": tru }\n  ]\n}";
+        let error = Json::<Routes>::parse(data.into()).expect_err("Invalid literal");
+        let error = error
+            .downcast_ref::<DeserializeError>()
+            .expect("Expected a deserialize error");
+
+        assert_eq!(error.line(), Some(3));
+        assert!(error.column().is_some());
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+    struct Payments {
+        port: u16,
+        hosts: Vec<String>,
+    }
+
+    const SERVICES: &str = r#"{
+        "version": 3,
+        "services": {
+            "billing": {"port": 1, "hosts": ["b"], "extra": [1, {"a": null}]},
+            "pay/ments": [{"skip": true}, {"port": 8080, "hosts": ["a", "c"]}]
+        }
+    }"#;
+
+    #[test]
+    fn test_parsing_pointer() {
+        use conditional_s3_fetch::{JsonAt, ParseContext, ParseWithContext};
+
+        let context = ParseContext::new("test-bucket", "test-prefix");
+        let parser = JsonAt::<Payments>::new("/services/pay~1ments/1").expect("Valid pointer");
+        assert_eq!(
+            parser.parse_with_context(SERVICES.into(), &context).ok(),
+            Some(Payments {
+                port: 8080,
+                hosts: vec!["a".into(), "c".into()]
+            })
+        );
+
+        let parser = JsonAt::<u32>::new("/version").expect("Valid pointer");
+        assert_eq!(
+            parser.parse_with_context(SERVICES.into(), &context).ok(),
+            Some(3)
+        );
+
+        let parser = JsonAt::<Payments>::new("/services/payments").expect("Valid pointer");
+        let error = parser
+            .parse_with_context(SERVICES.into(), &context)
+            .expect_err("Missing value");
+        assert_eq!(error.to_string(), ".: No value at /services/payments");
+
+        let parser = JsonAt::<Payments>::new("/services/billing").expect("Valid pointer");
+        let error = parser
+            .parse_with_context(
+                r#"{"services": {"billing": {"port": "x"}}}"#.into(),
+                &context,
+            )
+            .expect_err("Port is not a number");
+        let error = error
+            .downcast_ref::<conditional_s3_fetch::DeserializeError>()
+            .expect("Expected a deserialize error");
+        assert_eq!(error.path(), "services.billing.port");
+        assert_eq!(error.line(), Some(1));
+
+        assert!(JsonAt::<Payments>::new("services").is_err());
+    }
 }
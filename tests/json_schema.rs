@@ -0,0 +1,160 @@
+#[cfg(feature = "json-schema")]
+mod parsing {
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        primitives::SdkBody,
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{
+        json_schema::SchemaViolations, DeserializeError, File, JsonSchema, ParseContext,
+        ParseWithContext,
+    };
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    const SCHEMA: &str = r#"{
+        "type": "object",
+        "properties": {
+            "mode": { "enum": ["fast", "safe"] },
+            "port": { "type": "integer", "minimum": 1 }
+        },
+        "required": ["mode"]
+    }"#;
+
+    #[derive(serde::Deserialize, Debug, Clone, PartialEq)]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    struct Settings {
+        mode: String,
+        #[serde(default)]
+        port: u16,
+    }
+
+    #[tokio::test]
+    async fn test_parsing_json_schema() {
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .body(SdkBody::from(r#"{"mode": "fast", "port": 8080}"#))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let parser = JsonSchema::<Settings>::from_json(SCHEMA).expect("Schema is valid");
+        let file = File::with_parser("test-bucket", "test-prefix", parser)
+            .fetch(&client)
+            .await
+            .expect("Failed to fetch file")
+            .expect("File is modified");
+
+        replay_client.assert_requests_match(&[]);
+        assert_eq!(
+            file.into_inner().ok(),
+            Some(Settings {
+                mode: "fast".into(),
+                port: 8080
+            })
+        );
+    }
+
+    #[test]
+    fn test_schema_violations() {
+        let parser = JsonSchema::<Settings>::from_json(SCHEMA).expect("Schema is valid");
+        let context = ParseContext::new("test-bucket", "test-prefix");
+
+        let error = parser
+            .parse_with_context(r#"{"mode": "slow", "port": 0}"#.into(), &context)
+            .expect_err("Document should violate the schema");
+        let violations = error
+            .downcast_ref::<SchemaViolations>()
+            .expect("Expected schema violations");
+
+        let mut paths: Vec<_> = violations
+            .violations()
+            .iter()
+            .map(|violation| (violation.instance_path(), violation.schema_path()))
+            .collect();
+        paths.sort_unstable();
+        assert_eq!(
+            paths,
+            [
+                ("/mode", "/properties/mode/enum"),
+                ("/port", "/properties/port/minimum")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_error_after_schema() {
+        let parser = JsonSchema::<Settings>::from_json(SCHEMA).expect("Schema is valid");
+        let context = ParseContext::new("test-bucket", "test-prefix");
+
+        let error = parser
+            .parse_with_context(r#"{"mode": "fast", "port": 70000}"#.into(), &context)
+            .expect_err("Port should not fit the field");
+        let error = error
+            .downcast_ref::<DeserializeError>()
+            .expect("Expected a deserialize error");
+        assert_eq!(error.path(), "port");
+
+        let error = parser
+            .parse_with_context("{\n\"mode\": }".into(), &context)
+            .expect_err("Document is not Json");
+        let error = error
+            .downcast_ref::<DeserializeError>()
+            .expect("Expected a deserialize error");
+        assert_eq!(error.line(), Some(2));
+    }
+
+    #[test]
+    fn test_invalid_schema() {
+        assert!(JsonSchema::<Settings>::from_json(r#"{"type": "unknown"}"#).is_err());
+        assert!(JsonSchema::<Settings>::from_json("not json").is_err());
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_generated_schema() {
+        let parser = JsonSchema::<Settings>::default();
+        let context = ParseContext::new("test-bucket", "test-prefix");
+
+        let parsed = parser
+            .parse_with_context(r#"{"mode": "safe"}"#.into(), &context)
+            .unwrap();
+        assert_eq!(
+            parsed,
+            Settings {
+                mode: "safe".into(),
+                port: 0
+            }
+        );
+
+        let error = parser
+            .parse_with_context(r#"{"port": -1}"#.into(), &context)
+            .expect_err("Document should violate the schema");
+        assert!(error.is::<SchemaViolations>());
+    }
+}
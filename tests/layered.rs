@@ -0,0 +1,158 @@
+mod common;
+
+#[cfg(all(feature = "layered", any(feature = "json", feature = "simd-json")))]
+mod parsing {
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{DeserializeError, Error, Json, Layered};
+
+    use crate::common::{not_found, not_modified, request_key, response};
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+    struct Limits {
+        requests: u32,
+        burst: Option<u32>,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+    struct Settings {
+        name: String,
+        hosts: Vec<String>,
+        limits: Limits,
+    }
+
+    fn layered() -> Layered<Json<Settings>> {
+        Layered::new("test-bucket")
+            .layer("base.json")
+            .optional_layer("env/prod.json")
+            .optional_layer("region/eu-west-1.json")
+    }
+
+    const BASE: &str =
+        r#"{"name": "payments", "hosts": ["a", "b"], "limits": {"requests": 10, "burst": 20}}"#;
+
+    #[tokio::test]
+    async fn test_merging_layers() {
+        let replay_client = StaticReplayClient::new(vec![
+            ReplayEvent::new(request_key("base.json", None), response("\"1\"", BASE)),
+            ReplayEvent::new(
+                request_key("env/prod.json", None),
+                response("\"2\"", r#"{"hosts": ["c"], "limits": {"burst": null}}"#),
+            ),
+            ReplayEvent::new(request_key("region/eu-west-1.json", None), not_found()),
+            ReplayEvent::new(request_key("base.json", Some("\"1\"")), not_modified()),
+            ReplayEvent::new(request_key("env/prod.json", Some("\"2\"")), not_modified()),
+            ReplayEvent::new(request_key("region/eu-west-1.json", None), not_found()),
+            ReplayEvent::new(request_key("base.json", Some("\"1\"")), not_modified()),
+            ReplayEvent::new(request_key("env/prod.json", Some("\"2\"")), not_found()),
+            ReplayEvent::new(
+                request_key("region/eu-west-1.json", None),
+                response("\"3\"", r#"{"limits": {"requests": 5}}"#),
+            ),
+        ]);
+        let client = test_client(replay_client.clone());
+
+        let config = layered()
+            .fetch(&client)
+            .await
+            .expect("Failed to fetch layers")
+            .expect("Layers are loaded");
+        assert_eq!(
+            config.as_content(),
+            Some(&Settings {
+                name: "payments".into(),
+                hosts: vec!["c".into()],
+                limits: Limits {
+                    requests: 10,
+                    burst: None
+                },
+            })
+        );
+        assert_eq!(
+            config.loaded_keys().collect::<Vec<_>>(),
+            vec!["base.json", "env/prod.json"]
+        );
+
+        let unchanged = config.fetch(&client).await.expect("Failed to fetch layers");
+        assert!(unchanged.is_none());
+
+        let config = config
+            .fetch(&client)
+            .await
+            .expect("Failed to fetch layers")
+            .expect("Layers are modified");
+        replay_client.assert_requests_match(&[]);
+        assert_eq!(
+            config.into_inner(),
+            Some(Settings {
+                name: "payments".into(),
+                hosts: vec!["a".into(), "b".into()],
+                limits: Limits {
+                    requests: 5,
+                    burst: Some(20)
+                },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_required_layer() {
+        let replay_client = StaticReplayClient::new(vec![ReplayEvent::new(
+            request_key("base.json", None),
+            not_found(),
+        )]);
+        let client = test_client(replay_client.clone());
+
+        let error = layered()
+            .fetch(&client)
+            .await
+            .expect_err("Base layer is required");
+        assert!(matches!(error, Error::NotFound { key, .. } if key == "base.json"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_merged_value() {
+        let replay_client = StaticReplayClient::new(vec![
+            ReplayEvent::new(request_key("base.json", None), response("\"1\"", BASE)),
+            ReplayEvent::new(
+                request_key("env/prod.json", None),
+                response("\"2\"", r#"{"limits": {"requests": "many"}}"#),
+            ),
+            ReplayEvent::new(request_key("region/eu-west-1.json", None), not_found()),
+        ]);
+        let client = test_client(replay_client.clone());
+
+        let error = layered()
+            .fetch(&client)
+            .await
+            .expect_err("Merged value is invalid");
+        let Error::ParseError(error) = error else {
+            panic!("Expected a parse error, got {error:?}");
+        };
+        let error = error
+            .downcast_ref::<DeserializeError>()
+            .expect("Expected a deserialize error");
+        assert_eq!(error.path(), "limits.requests");
+    }
+}
@@ -0,0 +1,143 @@
+use aws_sdk_s3::{
+    config::{Credentials, Region},
+    primitives::SdkBody,
+    Client, Config,
+};
+use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+use conditional_s3_fetch::{Completion, Executor, File, Offload, Task};
+
+fn test_client(replay_client: StaticReplayClient) -> Client {
+    Client::from_conf(
+        Config::builder()
+            .behavior_version_latest()
+            .credentials_provider(Credentials::new(
+                "ATESTCLIENT",
+                "astestsecretkey",
+                Some("atestsessiontoken".to_string()),
+                None,
+                "",
+            ))
+            .region(Region::new("us-east-1"))
+            .http_client(replay_client)
+            .build(),
+    )
+}
+
+fn response() -> ReplayEvent {
+    ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"123\"")
+            .body(SdkBody::from("hello"))
+            .unwrap(),
+    )
+}
+
+/// Executor which only counts the scheduled tasks, running them on a new thread
+#[derive(Default, Clone)]
+struct CountingExecutor(std::sync::Arc<std::sync::atomic::AtomicUsize>);
+
+impl Executor for CountingExecutor {
+    fn spawn_blocking(&self, task: Task) -> Completion {
+        self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        std::thread::spawn(move || {
+            task();
+            let _ = sender.send(());
+        });
+        Box::pin(async move {
+            let _ = receiver.await;
+        })
+    }
+}
+
+/// Executor which drops every task without running it
+struct DroppingExecutor;
+
+impl Executor for DroppingExecutor {
+    fn spawn_blocking(&self, _task: Task) -> Completion {
+        Box::pin(async {})
+    }
+}
+
+#[tokio::test]
+async fn test_offloading_large_files() {
+    let replay_client = StaticReplayClient::new(vec![response()]);
+    let client = test_client(replay_client.clone());
+    let executor = CountingExecutor::default();
+    let offload = Offload::new(1, executor.clone());
+
+    let file = File::<String>::unloaded("test-bucket", "test-prefix")
+        .fetch_offloaded(&client, &offload)
+        .await
+        .expect("Failed to fetch file");
+
+    replay_client.assert_requests_match(&[]);
+    assert_eq!(
+        file.as_ref()
+            .and_then(|f| f.as_content())
+            .map(|f| f.as_str()),
+        Some("hello")
+    );
+    assert_eq!(executor.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_parsing_small_files_inline() {
+    let replay_client = StaticReplayClient::new(vec![response()]);
+    let client = test_client(replay_client.clone());
+    let executor = CountingExecutor::default();
+    let offload = Offload::new(1024, executor.clone());
+
+    let file = File::<String>::unloaded("test-bucket", "test-prefix")
+        .fetch_offloaded(&client, &offload)
+        .await
+        .expect("Failed to fetch file");
+
+    assert!(file.is_some());
+    assert_eq!(executor.0.load(std::sync::atomic::Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_dropped_tasks_fail_the_fetch() {
+    let replay_client = StaticReplayClient::new(vec![response()]);
+    let client = test_client(replay_client.clone());
+    let offload = Offload::new(0, DroppingExecutor);
+
+    let file = File::<String>::unloaded("test-bucket", "test-prefix")
+        .fetch_offloaded(&client, &offload)
+        .await;
+
+    assert!(matches!(
+        file,
+        Err(conditional_s3_fetch::Error::ParseAborted)
+    ));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_offloading_to_tokio_blocking_pool() {
+    use conditional_s3_fetch::SpawnBlocking;
+
+    let replay_client = StaticReplayClient::new(vec![response()]);
+    let client = test_client(replay_client.clone());
+    let offload = Offload::new(0, SpawnBlocking);
+
+    let file = File::<String>::unloaded("test-bucket", "test-prefix")
+        .fetch_offloaded(&client, &offload)
+        .await
+        .expect("Failed to fetch file");
+
+    assert_eq!(
+        file.as_ref()
+            .and_then(|f| f.as_content())
+            .map(|f| f.as_str()),
+        Some("hello")
+    );
+}
@@ -0,0 +1,86 @@
+#[cfg(feature = "postcard")]
+mod parsing {
+    use std::ops::Deref;
+
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        primitives::SdkBody,
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{File, Parse, Postcard};
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(serde::Deserialize, serde::Serialize, Eq, PartialEq, Debug)]
+    struct MyStruct {
+        key: String,
+    }
+
+    #[tokio::test]
+    async fn test_parsing_postcard() {
+        let response = postcard::to_stdvec(&MyStruct {
+            key: "value".to_string(),
+        })
+        .unwrap();
+
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .body(SdkBody::from(response))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::<Postcard<MyStruct>>::loaded("test-bucket", "test-prefix", &client)
+            .await
+            .expect("Failed to fetch file");
+
+        replay_client.assert_requests_match(&[]);
+        assert_eq!(
+            file.as_content().map(|f| f.deref()),
+            Some(&MyStruct {
+                key: "value".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parsing_over_limit() {
+        let encoded = postcard::to_stdvec(&MyStruct {
+            key: "value".to_string(),
+        })
+        .unwrap();
+
+        assert!(Postcard::<MyStruct, 64>::parse(encoded.clone().into()).is_ok());
+        assert!(Postcard::<MyStruct, 4>::parse(encoded.into()).is_err());
+    }
+
+    #[test]
+    fn test_parsing_failure() {
+        assert!(Postcard::<MyStruct>::parse(bytes::Bytes::from_static(&[0xff])).is_err());
+    }
+}
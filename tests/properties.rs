@@ -0,0 +1,109 @@
+#[cfg(feature = "properties")]
+mod parsing {
+    use std::ops::Deref;
+
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        primitives::SdkBody,
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{File, OrderedMap, Parse, Properties};
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(serde::Deserialize, Eq, PartialEq, Debug)]
+    struct MyStruct {
+        #[serde(rename = "server.port")]
+        port: u16,
+        #[serde(rename = "server.name")]
+        name: String,
+        message: String,
+        #[serde(rename = "key with spaces")]
+        spaced: String,
+    }
+
+    const PROPERTIES: &str = r#"
+# Server configuration
+! also a comment
+server.port = 8080
+server.name: café
+message Hello, \
+        world\tagain
+key\ with\ spaces=value
+"#;
+
+    #[tokio::test]
+    async fn test_parsing_properties() {
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .body(SdkBody::from(PROPERTIES))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::<Properties<MyStruct>>::loaded("test-bucket", "test-prefix", &client)
+            .await
+            .expect("Failed to fetch file");
+
+        replay_client.assert_requests_match(&[]);
+        assert_eq!(
+            file.as_content().map(|f| f.deref()),
+            Some(&MyStruct {
+                port: 8080,
+                name: "café".to_string(),
+                message: "Hello, world\tagain".to_string(),
+                spaced: "value".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parsing_ordered_map() {
+        let map = Properties::<OrderedMap>::parse("b=2\na:1\nc 3\nempty\n".into()).unwrap();
+
+        assert_eq!(map.get("c"), Some(&"3".to_string()));
+        assert_eq!(
+            map.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+            vec!["b", "a", "c", "empty"]
+        );
+    }
+
+    #[test]
+    fn test_parsing_surrogate_pairs() {
+        let map = Properties::<OrderedMap>::parse("k=\\uD83D\\uDE00 \\u00e9".into()).unwrap();
+
+        assert_eq!(map.get("k"), Some(&"\u{1F600} é".to_string()));
+    }
+
+    #[test]
+    fn test_parsing_failure() {
+        assert!(Properties::<OrderedMap>::parse("key=\\u12".into()).is_err());
+        assert!(Properties::<OrderedMap>::parse("key=\\uD83D".into()).is_err());
+        assert!(Properties::<OrderedMap>::parse("key=\\uD83D\\u0041".into()).is_err());
+    }
+}
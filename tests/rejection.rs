@@ -0,0 +1,176 @@
+mod common;
+
+use aws_sdk_s3::{
+    config::{Credentials, Region},
+    Client, Config,
+};
+use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+use conditional_s3_fetch::{BoxedResult, Error, File, Parse, ParseContext, Validated, Validator};
+
+use crate::common::{not_modified, request, response};
+
+fn test_client(replay_client: StaticReplayClient) -> Client {
+    Client::from_conf(
+        Config::builder()
+            .behavior_version_latest()
+            .credentials_provider(Credentials::new(
+                "ATESTCLIENT",
+                "astestsecretkey",
+                Some("atestsessiontoken".to_string()),
+                None,
+                "",
+            ))
+            .region(Region::new("us-east-1"))
+            .http_client(replay_client)
+            .build(),
+    )
+}
+
+#[derive(Default)]
+struct NotBlank;
+
+impl Validator<String> for NotBlank {
+    fn validate(&self, text: &String, _context: &ParseContext) -> BoxedResult<()> {
+        if text.trim().is_empty() {
+            return Err("Content must not be empty".into());
+        }
+        Ok(())
+    }
+}
+
+/// Parser failing with a transient error, as a lookup done while parsing would
+#[derive(Default)]
+struct Unavailable;
+
+impl Parse for Unavailable {
+    type Output = String;
+
+    fn parse(bytes: bytes::Bytes) -> BoxedResult<Self::Output> {
+        if bytes.as_ref() == b"unavailable" {
+            return Err("Lookup service is unavailable".into());
+        }
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+}
+
+#[tokio::test]
+async fn test_rejected_version_keeps_last_good_version() {
+    let replay_client = StaticReplayClient::new(vec![
+        ReplayEvent::new(request(None), response("\"1\"", "hello")),
+        ReplayEvent::new(request(Some("\"1\"")), response("\"2\"", " ")),
+        ReplayEvent::new(request(Some("\"2\"")), not_modified()),
+        ReplayEvent::new(request(Some("\"2\"")), response("\"3\"", "world")),
+    ]);
+    let client = test_client(replay_client.clone());
+
+    let file = File::<Validated<String, NotBlank>>::unloaded("test-bucket", "test-prefix")
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is modified");
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("hello"));
+    assert_eq!(file.rejected_etag(), None);
+
+    let error = file
+        .fetch(&client)
+        .await
+        .expect_err("Empty content should be rejected");
+    let Error::Rejected { etag, rejection } = error else {
+        panic!("Expected a rejection, got {error:?}");
+    };
+    assert_eq!(etag, "\"2\"");
+    assert_eq!(rejection.to_string(), "Content must not be empty");
+    assert_eq!(file.rejected_etag().as_deref(), Some("\"2\""));
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("hello"));
+
+    let unchanged = file.fetch(&client).await.expect("Failed to fetch file");
+    assert!(unchanged.is_none());
+
+    let file = file
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is modified");
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("world"));
+    assert_eq!(file.rejected_etag(), None);
+
+    replay_client.assert_requests_match(&[]);
+}
+
+#[tokio::test]
+async fn test_rejected_first_version() {
+    let replay_client =
+        StaticReplayClient::new(vec![ReplayEvent::new(request(None), response("\"1\"", ""))]);
+    let client = test_client(replay_client.clone());
+
+    let file = File::<Validated<String, NotBlank>>::unloaded("test-bucket", "test-prefix");
+    let error = file
+        .fetch(&client)
+        .await
+        .expect_err("Empty content should be rejected");
+    assert!(matches!(error, Error::Rejected { .. }));
+    assert_eq!(file.rejected_etag(), None);
+}
+
+#[tokio::test]
+async fn test_malformed_version_is_not_downloaded_again() {
+    let replay_client = StaticReplayClient::new(vec![
+        ReplayEvent::new(request(None), response("\"1\"", "hello")),
+        ReplayEvent::new(request(Some("\"1\"")), response("\"2\"", vec![0xff, 0xfe])),
+        ReplayEvent::new(request(Some("\"2\"")), not_modified()),
+    ]);
+    let client = test_client(replay_client.clone());
+
+    let file = File::<String>::unloaded("test-bucket", "test-prefix")
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is modified");
+
+    let error = file
+        .fetch(&client)
+        .await
+        .expect_err("Invalid UTF-8 should fail to parse");
+    assert!(matches!(error, Error::ParseError(_)));
+    assert_eq!(file.rejected_etag().as_deref(), Some("\"2\""));
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("hello"));
+
+    let unchanged = file.fetch(&client).await.expect("Failed to fetch file");
+    assert!(unchanged.is_none());
+
+    replay_client.assert_requests_match(&[]);
+}
+
+#[tokio::test]
+async fn test_transient_failure_is_retried() {
+    let replay_client = StaticReplayClient::new(vec![
+        ReplayEvent::new(request(None), response("\"1\"", "hello")),
+        ReplayEvent::new(request(Some("\"1\"")), response("\"2\"", "unavailable")),
+        ReplayEvent::new(request(Some("\"1\"")), response("\"3\"", "world")),
+    ]);
+    let client = test_client(replay_client.clone());
+
+    let file = File::<Unavailable>::unloaded("test-bucket", "test-prefix")
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is modified");
+
+    let error = file
+        .fetch(&client)
+        .await
+        .expect_err("Lookup should fail while parsing");
+    assert!(matches!(error, Error::ParseError(_)));
+    assert_eq!(file.rejected_etag(), None);
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("hello"));
+
+    let file = file
+        .fetch(&client)
+        .await
+        .expect("Failed to fetch file")
+        .expect("File is modified");
+    assert_eq!(file.as_content().map(|c| c.as_str()), Some("world"));
+
+    replay_client.assert_requests_match(&[]);
+}
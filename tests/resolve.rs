@@ -0,0 +1,186 @@
+mod common;
+
+#[cfg(all(feature = "resolve", any(feature = "json", feature = "simd-json")))]
+mod parsing {
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{resolve::ReferenceError, Error, Json, Resolved};
+
+    use crate::common::{not_modified, request_object, response};
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+    struct Timeouts {
+        connect_ms: u32,
+        read_ms: u32,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+    struct Route {
+        path: String,
+        timeouts: Timeouts,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+    struct Routes {
+        routes: Vec<Route>,
+    }
+
+    const ROUTES: &str = r#"{"routes": [
+        {"path": "/a", "timeouts": {"$ref": "s3://cfg/common/timeouts.json"}},
+        {"path": "/b", "timeouts": {"$ref": "s3://shared/timeouts.json#/slow"}}
+    ]}"#;
+
+    #[tokio::test]
+    async fn test_resolving_references() {
+        let replay_client = StaticReplayClient::new(vec![
+            ReplayEvent::new(
+                request_object("test-bucket", "routes.json", None),
+                response("\"1\"", ROUTES),
+            ),
+            ReplayEvent::new(
+                request_object("shared", "timeouts.json", None),
+                response(
+                    "\"2\"",
+                    r#"{"slow": {"connect_ms": 100, "read_ms": {"$ref": "s3://cfg/common/read.json"}}}"#,
+                ),
+            ),
+            ReplayEvent::new(
+                request_object("cfg", "common/read.json", None),
+                response("\"3\"", "5000"),
+            ),
+            ReplayEvent::new(
+                request_object("cfg", "common/timeouts.json", None),
+                response("\"4\"", r#"{"connect_ms": 10, "read_ms": 50}"#),
+            ),
+            ReplayEvent::new(
+                request_object("test-bucket", "routes.json", Some("\"1\"")),
+                not_modified(),
+            ),
+            ReplayEvent::new(
+                request_object("shared", "timeouts.json", Some("\"2\"")),
+                not_modified(),
+            ),
+            ReplayEvent::new(
+                request_object("cfg", "common/read.json", Some("\"3\"")),
+                response("\"5\"", "9000"),
+            ),
+            ReplayEvent::new(
+                request_object("cfg", "common/timeouts.json", Some("\"4\"")),
+                not_modified(),
+            ),
+        ]);
+        let client = test_client(replay_client.clone());
+
+        let routes = Resolved::<Json<Routes>>::new("test-bucket", "routes.json")
+            .fetch(&client)
+            .await
+            .expect("Failed to fetch references")
+            .expect("Document is loaded");
+        assert_eq!(
+            routes.as_content().map(|r| &r.routes[1].timeouts),
+            Some(&Timeouts {
+                connect_ms: 100,
+                read_ms: 5000
+            })
+        );
+        let mut references: Vec<_> = routes.references().collect();
+        references.sort_unstable();
+        assert_eq!(
+            references,
+            vec![
+                "s3://cfg/common/read.json",
+                "s3://cfg/common/timeouts.json",
+                "s3://shared/timeouts.json"
+            ]
+        );
+
+        let routes = routes
+            .fetch(&client)
+            .await
+            .expect("Failed to fetch references")
+            .expect("Referenced object is modified");
+        replay_client.assert_requests_match(&[]);
+        assert_eq!(
+            routes.into_inner(),
+            Some(Routes {
+                routes: vec![
+                    Route {
+                        path: "/a".into(),
+                        timeouts: Timeouts {
+                            connect_ms: 10,
+                            read_ms: 50
+                        }
+                    },
+                    Route {
+                        path: "/b".into(),
+                        timeouts: Timeouts {
+                            connect_ms: 100,
+                            read_ms: 9000
+                        }
+                    },
+                ]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejecting_cyclic_references() {
+        let replay_client = StaticReplayClient::new(vec![
+            ReplayEvent::new(
+                request_object("test-bucket", "routes.json", None),
+                response("\"1\"", r#"{"routes": {"$ref": "s3://cfg/a.json"}}"#),
+            ),
+            ReplayEvent::new(
+                request_object("cfg", "a.json", None),
+                response("\"2\"", r#"{"$ref": "s3://cfg/b.json"}"#),
+            ),
+            ReplayEvent::new(
+                request_object("cfg", "b.json", None),
+                response("\"3\"", r#"[{"$ref": "s3://cfg/a.json"}]"#),
+            ),
+        ]);
+        let client = test_client(replay_client.clone());
+
+        let error = Resolved::<Json<Routes>>::new("test-bucket", "routes.json")
+            .fetch(&client)
+            .await
+            .expect_err("References are cyclic");
+        replay_client.assert_requests_match(&[]);
+
+        let Error::ParseError(error) = error else {
+            panic!("Expected a parse error, got {error:?}");
+        };
+        assert_eq!(
+            error.downcast_ref::<ReferenceError>(),
+            Some(&ReferenceError::Cycle {
+                chain: vec![
+                    "s3://test-bucket/routes.json".to_string(),
+                    "s3://cfg/a.json".to_string(),
+                    "s3://cfg/b.json".to_string(),
+                    "s3://cfg/a.json".to_string(),
+                ]
+            })
+        );
+    }
+}
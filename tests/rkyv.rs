@@ -0,0 +1,117 @@
+#[cfg(feature = "rkyv")]
+mod parsing {
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        primitives::SdkBody,
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{File, Parse, Rkyv};
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(rkyv::Archive, rkyv::Serialize, Debug)]
+    #[rkyv(derive(Debug))]
+    struct MyStruct {
+        key: String,
+        values: Vec<u64>,
+    }
+
+    fn encoded() -> Vec<u8> {
+        rkyv::to_bytes::<rkyv::rancor::Error>(&MyStruct {
+            key: "value".to_string(),
+            values: vec![1, 2, 3],
+        })
+        .unwrap()
+        .to_vec()
+    }
+
+    #[tokio::test]
+    async fn test_parsing_rkyv() {
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .body(SdkBody::from(encoded()))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::<Rkyv<MyStruct>>::loaded("test-bucket", "test-prefix", &client)
+            .await
+            .expect("Failed to fetch file");
+
+        replay_client.assert_requests_match(&[]);
+        let content = file.as_content().expect("File is loaded");
+        assert_eq!(content.key, "value");
+        assert_eq!(content.values.as_slice(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_parsing_misaligned_content() {
+        let mut buffer = vec![0];
+        buffer.extend(encoded());
+        let misaligned = bytes::Bytes::from(buffer).slice(1..);
+
+        let content = Rkyv::<MyStruct>::parse(misaligned).expect("Failed to parse");
+        assert_eq!(content.key, "value");
+        assert_eq!(content.as_bytes(), encoded().as_slice());
+    }
+
+    #[derive(rkyv::Archive, rkyv::Serialize)]
+    struct Nested {
+        count: u32,
+        values: Vec<u64>,
+    }
+
+    #[test]
+    fn test_parsing_content_misaligned_for_nested_values() {
+        let encoded = rkyv::to_bytes::<rkyv::rancor::Error>(&Nested {
+            count: 2,
+            values: vec![1, 2],
+        })
+        .unwrap();
+        assert_eq!(std::mem::align_of::<ArchivedNested>(), 4);
+
+        let mut buffer = Vec::with_capacity(encoded.len() + 8);
+        let offset = (12 - buffer.as_ptr() as usize % 8) % 8;
+        buffer.resize(offset, 0);
+        buffer.extend_from_slice(&encoded);
+        let misaligned = bytes::Bytes::from(buffer).slice(offset..);
+        assert_eq!(misaligned.as_ptr() as usize % 8, 4);
+
+        let content = Rkyv::<Nested>::parse(misaligned).expect("Failed to parse");
+        assert_eq!(content.count, 2);
+        assert_eq!(content.values.as_slice(), [1, 2]);
+    }
+
+    #[test]
+    fn test_parsing_failure() {
+        let mut corrupted = encoded();
+        corrupted.truncate(corrupted.len() - 4);
+
+        assert!(Rkyv::<MyStruct>::parse(corrupted.into()).is_err());
+    }
+}
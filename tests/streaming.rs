@@ -0,0 +1,175 @@
+use aws_sdk_s3::{
+    config::{Credentials, Region},
+    primitives::SdkBody,
+    Client, Config,
+};
+use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+use conditional_s3_fetch::{File, Lines, ParseContext, StreamParse};
+
+fn test_client(replay_client: StaticReplayClient) -> Client {
+    Client::from_conf(
+        Config::builder()
+            .behavior_version_latest()
+            .credentials_provider(Credentials::new(
+                "ATESTCLIENT",
+                "astestsecretkey",
+                Some("atestsessiontoken".to_string()),
+                None,
+                "",
+            ))
+            .region(Region::new("us-east-1"))
+            .http_client(replay_client)
+            .build(),
+    )
+}
+
+fn feed_chunks<P: StreamParse + Default>(chunks: &[&'static str]) -> P::Output {
+    let parser = P::default();
+    let context = ParseContext::new("test-bucket", "test-prefix");
+    let mut state = parser.start(&context);
+    for chunk in chunks {
+        parser
+            .feed(&mut state, bytes::Bytes::from_static(chunk.as_bytes()))
+            .unwrap();
+    }
+    parser.finish(state, &context).unwrap()
+}
+
+#[tokio::test]
+async fn test_streaming_lines() {
+    let req1 = ReplayEvent::new(
+        http::Request::builder()
+            .method("GET")
+            .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http::Response::builder()
+            .status(200)
+            .header("ETag", "\"123\"")
+            .body(SdkBody::from("first\r\nsecond\nthird"))
+            .unwrap(),
+    );
+    let replay_client = StaticReplayClient::new(vec![req1]);
+    let client = test_client(replay_client.clone());
+
+    let file = File::<Lines>::loaded("test-bucket", "test-prefix", &client)
+        .await
+        .expect("Failed to fetch file");
+
+    replay_client.assert_requests_match(&[]);
+    assert_eq!(
+        file.into_inner().ok(),
+        Some(vec![
+            "first".to_string(),
+            "second".to_string(),
+            "third".to_string()
+        ])
+    );
+}
+
+#[test]
+fn test_lines_split_across_chunks() {
+    let lines = feed_chunks::<Lines>(&["fi", "rst\nsec", "ond\r", "\n", "\nlast\n"]);
+
+    assert_eq!(lines, vec!["first", "second", "", "last"]);
+}
+
+#[test]
+fn test_buffered_parse_across_chunks() {
+    let text = feed_chunks::<String>(&["hel", "lo ", "world"]);
+
+    assert_eq!(text, "hello world");
+}
+
+#[cfg(any(feature = "json", feature = "simd-json"))]
+mod ndjson {
+    use super::*;
+    use conditional_s3_fetch::NdJson;
+
+    #[derive(serde::Deserialize, Clone, Eq, PartialEq, Debug)]
+    struct Event {
+        id: u32,
+    }
+
+    #[tokio::test]
+    async fn test_streaming_ndjson() {
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .body(SdkBody::from("{\"id\": 1}\n\n{\"id\": 2}\n"))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::<NdJson<Event>>::loaded("test-bucket", "test-prefix", &client)
+            .await
+            .expect("Failed to fetch file");
+
+        replay_client.assert_requests_match(&[]);
+        assert_eq!(
+            file.into_inner().ok(),
+            Some(vec![Event { id: 1 }, Event { id: 2 }])
+        );
+    }
+
+    #[test]
+    fn test_ndjson_split_across_chunks() {
+        let events = feed_chunks::<NdJson<Event>>(&["{\"id\"", ": 1}\n{\"id\": ", "2}"]);
+
+        assert_eq!(events, vec![Event { id: 1 }, Event { id: 2 }]);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_failure() {
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .body(SdkBody::from("{\"id\": 1}\nbad data\n"))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::<NdJson<Event>>::loaded("test-bucket", "test-prefix", &client).await;
+
+        assert!(matches!(
+            file,
+            Err(conditional_s3_fetch::Error::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_ndjson_failure_line() {
+        use conditional_s3_fetch::DeserializeError;
+
+        let parser = NdJson::<Event>::default();
+        let context = ParseContext::new("test-bucket", "test-prefix");
+        let mut state = parser.start(&context);
+        parser
+            .feed(&mut state, "{\"id\": 1}\n\n{\"id\"".into())
+            .unwrap();
+
+        let error = parser
+            .feed(&mut state, ": \"x\"}\n".into())
+            .expect_err("Id is not a number");
+        let error = error
+            .downcast_ref::<DeserializeError>()
+            .expect("Expected a deserialize error");
+        assert_eq!(error.path(), "id");
+        assert_eq!(error.line(), Some(3));
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(any(feature = "simd-json", feature = "json"))]
+mod parsing {
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        primitives::SdkBody,
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{File, Json, Parse, WarnUnknown};
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(serde::Deserialize, Debug, Clone, PartialEq)]
+    struct Settings {
+        #[serde(default)]
+        routes: Vec<Route>,
+    }
+
+    #[derive(serde::Deserialize, Debug, Clone, PartialEq)]
+    struct Route {
+        #[serde(default)]
+        timeout_ms: u32,
+    }
+
+    #[tokio::test]
+    async fn test_parsing_unknown_fields() {
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .body(SdkBody::from(
+                    r#"{"routes": [{"timeout_ms": 10}, {"timout_ms": 20}], "debug": true}"#,
+                ))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let file =
+            File::<Json<WarnUnknown<Settings>>>::loaded("test-bucket", "test-prefix", &client)
+                .await
+                .expect("Failed to fetch file");
+
+        replay_client.assert_requests_match(&[]);
+        let content = file.into_inner().expect("File is loaded");
+        assert_eq!(content.ignored(), ["routes.1.timout_ms", "debug"]);
+        assert_eq!(content.routes[1].timeout_ms, 0);
+        assert_eq!(
+            content.into_inner(),
+            Settings {
+                routes: vec![Route { timeout_ms: 10 }, Route { timeout_ms: 0 }]
+            }
+        );
+    }
+
+    #[test]
+    fn test_parsing_known_fields() {
+        let content = Json::<WarnUnknown<Settings>>::parse(r#"{"routes": []}"#.into()).unwrap();
+        assert!(content.ignored().is_empty());
+    }
+}
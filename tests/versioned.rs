@@ -0,0 +1,200 @@
+#[cfg(any(feature = "json", feature = "simd-json"))]
+mod parsing {
+    use aws_sdk_s3::{
+        config::{Credentials, Region},
+        primitives::SdkBody,
+        Client, Config,
+    };
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+
+    use conditional_s3_fetch::{
+        Error, File, Json, ParseContext, ParseWithContext, VersionError, VersionMarker, Versioned,
+    };
+
+    fn test_client(replay_client: StaticReplayClient) -> Client {
+        Client::from_conf(
+            Config::builder()
+                .behavior_version_latest()
+                .credentials_provider(Credentials::new(
+                    "ATESTCLIENT",
+                    "astestsecretkey",
+                    Some("atestsessiontoken".to_string()),
+                    None,
+                    "",
+                ))
+                .region(Region::new("us-east-1"))
+                .http_client(replay_client)
+                .build(),
+        )
+    }
+
+    #[derive(serde::Deserialize)]
+    struct SettingsV1 {
+        timeout: u32,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct SettingsV2 {
+        timeout_ms: u32,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq, Eq)]
+    struct Settings {
+        timeout_ms: u32,
+        retries: u32,
+    }
+
+    fn parser() -> Versioned<Json<Settings>> {
+        Versioned::<Json<Settings>>::starting_at::<SettingsV1>(1)
+            .migrate(|v1: SettingsV1| SettingsV2 {
+                timeout_ms: v1.timeout * 1000,
+            })
+            .migrate(|v2: SettingsV2| Settings {
+                timeout_ms: v2.timeout_ms,
+                retries: 3,
+            })
+            .build()
+    }
+
+    fn context() -> ParseContext {
+        ParseContext::new("test-bucket", "test-prefix")
+    }
+
+    #[tokio::test]
+    async fn test_migrating_from_metadata_version() {
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .header("x-amz-meta-schema-version", "1")
+                .body(SdkBody::from(r#"{"timeout": 5}"#))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let file = File::with_parser("test-bucket", "test-prefix", parser())
+            .fetch(&client)
+            .await
+            .expect("Failed to fetch file")
+            .expect("File is modified");
+
+        replay_client.assert_requests_match(&[]);
+        assert_eq!(
+            file.as_content().map(|c| &**c),
+            Some(&Settings {
+                timeout_ms: 5000,
+                retries: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_migrating_from_field_version() {
+        let parser = parser().with_marker(VersionMarker::Field("schema_version".into()));
+
+        let config = parser
+            .parse_with_context(
+                r#"{"schema_version": 2, "timeout_ms": 250}"#.into(),
+                &context(),
+            )
+            .expect("Version 2 should be migrated");
+        assert_eq!(
+            config,
+            Settings {
+                timeout_ms: 250,
+                retries: 3
+            }
+        );
+
+        let config = parser
+            .parse_with_context(
+                r#"{"schema_version": "3", "timeout_ms": 250, "retries": 1}"#.into(),
+                &context(),
+            )
+            .expect("Version 3 is the current shape");
+        assert_eq!(
+            config,
+            Settings {
+                timeout_ms: 250,
+                retries: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_rejecting_unsupported_versions() {
+        let parser = parser().with_marker(VersionMarker::Field("schema_version".into()));
+        assert_eq!(parser.oldest_version(), 1);
+        assert_eq!(parser.latest_version(), 3);
+
+        let error = parser
+            .parse_with_context(r#"{"schema_version": 4}"#.into(), &context())
+            .expect_err("Version 4 is unknown");
+        assert_eq!(
+            error.downcast_ref::<VersionError>(),
+            Some(&VersionError::TooNew {
+                version: 4,
+                latest: 3
+            })
+        );
+        assert_eq!(
+            error.to_string(),
+            "Schema version 4 is newer than the latest supported version 3"
+        );
+
+        let error = parser
+            .parse_with_context(r#"{"timeout_ms": 250}"#.into(), &context())
+            .expect_err("Version is missing");
+        assert!(matches!(
+            error.downcast_ref::<VersionError>(),
+            Some(VersionError::Missing { .. })
+        ));
+
+        let config = parser
+            .with_default_version(2)
+            .parse_with_context(r#"{"timeout_ms": 250}"#.into(), &context())
+            .expect("Missing version defaults to 2");
+        assert_eq!(config.retries, 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetching_newer_version_fails() {
+        let req1 = ReplayEvent::new(
+            http::Request::builder()
+                .method("GET")
+                .uri("https://test-bucket.s3.us-east-1.amazonaws.com/test-prefix?x-id=GetObject")
+                .body(SdkBody::empty())
+                .unwrap(),
+            http::Response::builder()
+                .status(200)
+                .header("ETag", "\"123\"")
+                .header("x-amz-meta-schema-version", "7")
+                .body(SdkBody::from(r#"{"timeout_ms": 5, "retries": 1}"#))
+                .unwrap(),
+        );
+        let replay_client = StaticReplayClient::new(vec![req1]);
+        let client = test_client(replay_client.clone());
+
+        let error = File::with_parser("test-bucket", "test-prefix", parser())
+            .fetch(&client)
+            .await
+            .expect_err("Version 7 is unknown");
+
+        let Error::ParseError(error) = error else {
+            panic!("Expected a parse error, got {error:?}");
+        };
+        assert!(matches!(
+            error.downcast_ref::<VersionError>(),
+            Some(VersionError::TooNew {
+                version: 7,
+                latest: 3
+            })
+        ));
+    }
+}